@@ -1,5 +1,5 @@
 use bevy::{diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin}, prelude::*, window::PrimaryWindow};
-use bevy_quick_response::{QuickResponseMode, QuickResponseParameters, QuickResponsePlugin};
+use bevy_quick_response::{QuickResponseMode, QuickResponseParameters, QuickResponsePlugin, QuickResponsePlugins};
 
 pub fn close_on_esc(
     mut commands: Commands,
@@ -21,19 +21,15 @@ fn main() {
     let quick_response_plugin = QuickResponsePlugin::new(
         QuickResponseMode::FastVsync(QuickResponseParameters {
             base_fps: 60.0, // Base FPS, for example: when window is not focused
-            max_fps: 60.0, // Max FPS, for example: when mouse moves over window
-            auto_init_default_plugins: false, // Disable DefaultPlugin initialization
+            max_fps: 60.0.into(), // Max FPS, for example: when mouse moves over window
+            ..default()
         })
     );
 
-    let mut window_plugin = quick_response_plugin.window_plugin();
-    window_plugin.primary_window.as_mut().unwrap().title = "Advanced Example".to_string();
-
     App::new()
-        .add_plugins(DefaultPlugins.set(
-            window_plugin
-        ))
-        .add_plugins(quick_response_plugin)
+        // DefaultPlugins + QuickResponsePlugin, with the customized window
+        .add_plugins(QuickResponsePlugins::new(quick_response_plugin)
+            .window(|window| window.title = "Advanced Example".to_string()))
         .add_plugins(FrameTimeDiagnosticsPlugin::default())
         .add_systems(Update, close_on_esc)
         .add_systems(Startup, setup_camera)
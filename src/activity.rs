@@ -0,0 +1,260 @@
+use std::time::Duration;
+
+use bevy::{input::{gamepad::{GamepadAxisChangedEvent, GamepadButtonChangedEvent, GamepadConnectionEvent}, touch::TouchPhase}, platform::time::Instant, prelude::*, window::{WindowMoved, WindowResized}};
+
+use crate::{IdleTier, QuickResponsePacing};
+
+/// Settings for activities which boost the app to max fps (continuous update),
+/// in addition to the input events which wake winit anyway.
+///
+/// inserted as a resource by [`QuickResponsePlugin`](crate::QuickResponsePlugin),
+/// insert your own before adding the plugin (or modify it later) to customize.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct ActivityBoostSettings {
+    /// keep boosting until windows are not resized or moved for this period.
+    /// `Duration::ZERO` disables the boost.
+    /// default: 300ms
+    pub resize_grace_period: Duration,
+    /// gamepad axis values within this range are not regarded as activity (stick drift).
+    /// default: 0.15
+    pub gamepad_deadzone: f32,
+    /// keep boosting until no gamepad activity happens for this period.
+    /// `Duration::ZERO` disables the boost.
+    /// default: 1s
+    pub gamepad_grace_period: Duration,
+    /// winit does not wake the event loop for gamepad input,
+    /// so wake up at least this often while a gamepad is in use and a window is focused
+    /// (within `gamepad_grace_period` after the last gamepad activity).
+    /// `Duration::ZERO` disables the polling.
+    /// default: 1/60s
+    pub gamepad_poll_interval: Duration,
+    /// wake up at least this often while a gamepad is connected but not in use (and a window is focused), to notice the next input.
+    /// `Duration::ZERO` disables the polling (gamepad input is noticed on the next wake-up by other events).
+    /// default: 250ms
+    pub gamepad_idle_poll_interval: Duration,
+    /// keep boosting for this period after touch (or pen) input starts or moves.
+    /// `Duration::ZERO` disables the boost.
+    /// default: 300ms
+    pub touch_grace_period: Duration,
+    /// keep boosting for this period after a touch ends (momentum of fling scrolling).
+    /// `Duration::ZERO` disables the boost.
+    /// default: 1s
+    pub touch_momentum_period: Duration,
+    /// lower update rates after long inactivity (see [`IdleTier`]).
+    /// default: no tiers
+    pub idle_tiers: Vec<IdleTier>,
+    /// boost for this period on the first input in an idle tier.
+    /// `Duration::ZERO` disables the boost.
+    /// default: 500ms
+    pub idle_exit_boost: Duration,
+}
+
+impl Default for ActivityBoostSettings {
+    fn default() -> Self {
+        ActivityBoostSettings {
+            resize_grace_period: Duration::from_millis(300),
+            gamepad_deadzone: 0.15,
+            gamepad_grace_period: Duration::from_secs(1),
+            gamepad_poll_interval: Duration::from_secs_f64(1.0 / 60.0),
+            gamepad_idle_poll_interval: Duration::from_millis(250),
+            touch_grace_period: Duration::from_millis(300),
+            touch_momentum_period: Duration::from_secs(1),
+            idle_tiers: Vec::new(),
+            idle_exit_boost: Duration::from_millis(500),
+        }
+    }
+}
+
+pub(crate) fn detect_window_activity(
+    settings: Res<ActivityBoostSettings>,
+    mut resized: MessageReader<WindowResized>,
+    mut moved: MessageReader<WindowMoved>,
+    mut pacing: ResMut<QuickResponsePacing>,
+) {
+    // read both, so that old messages are not seen again
+    let resized = resized.read().count() > 0;
+    let moved = moved.read().count() > 0;
+
+    if (resized || moved) && !settings.resize_grace_period.is_zero() {
+        pacing.request_boost(settings.resize_grace_period);
+    }
+}
+
+/// poll fast while a gamepad is in use, slowly while one is connected but idle
+fn gamepad_poll_interval(settings: &ActivityBoostSettings, last_activity: Option<Instant>, now: Instant) -> Option<Duration> {
+    let in_use = last_activity.is_some_and(|last_activity| now.saturating_duration_since(last_activity) < settings.gamepad_grace_period);
+    let interval = if in_use { settings.gamepad_poll_interval } else { settings.gamepad_idle_poll_interval };
+    (!interval.is_zero()).then_some(interval)
+}
+
+pub(crate) fn detect_gamepad_activity(
+    settings: Res<ActivityBoostSettings>,
+    gamepads: Query<(), With<Gamepad>>,
+    mut connections: MessageReader<GamepadConnectionEvent>,
+    mut buttons: MessageReader<GamepadButtonChangedEvent>,
+    mut axes: MessageReader<GamepadAxisChangedEvent>,
+    mut pacing: ResMut<QuickResponsePacing>,
+    mut last_activity: Local<Option<Instant>>,
+) {
+    let connected = connections.read().count() > 0;
+    let pressed = buttons.read().count() > 0;
+    let moved = axes.read()
+        .filter(|axis| axis.value.abs() > settings.gamepad_deadzone)
+        .count() > 0;
+
+    let now = Instant::now();
+    if connected || pressed || moved {
+        *last_activity = Some(now);
+        if !settings.gamepad_grace_period.is_zero() {
+            pacing.request_boost(settings.gamepad_grace_period);
+        }
+    }
+
+    let poll_interval = if gamepads.is_empty() {
+        None
+    } else {
+        gamepad_poll_interval(&settings, *last_activity, now)
+    };
+    if pacing.poll_interval != poll_interval {
+        pacing.poll_interval = poll_interval;
+    }
+}
+
+/// pen input is reported as touch input (with force), so pressure changes are covered too
+pub(crate) fn detect_touch_activity(
+    settings: Res<ActivityBoostSettings>,
+    mut touches: MessageReader<TouchInput>,
+    mut pacing: ResMut<QuickResponsePacing>,
+) {
+    let mut boost = Duration::ZERO;
+
+    for touch in touches.read() {
+        let period = match touch.phase {
+            TouchPhase::Started | TouchPhase::Moved => settings.touch_grace_period,
+            TouchPhase::Ended | TouchPhase::Canceled => settings.touch_momentum_period,
+        };
+        boost = boost.max(period);
+    }
+
+    if !boost.is_zero() {
+        pacing.request_boost(boost);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::winit::{UpdateMode, WinitSettings};
+
+    use crate::QuickResponsePlugin;
+
+    use super::*;
+
+    #[test]
+    fn test_resize_boost() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .insert_resource(ActivityBoostSettings {
+                resize_grace_period: Duration::from_millis(50),
+                ..default()
+            })
+            .add_plugins(QuickResponsePlugin::power_saving(60.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+
+        let window = app.world_mut().spawn_empty().id();
+        app.world_mut().write_message(WindowResized { window, width: 640.0, height: 480.0 });
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::Continuous);
+
+        std::thread::sleep(Duration::from_millis(100));
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+    }
+
+    #[test]
+    fn test_gamepad_activity() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(QuickResponsePlugin::power_saving(60.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+
+        // connected but not in use: polled slowly
+        let gamepad = app.world_mut().spawn(Gamepad::default()).id();
+        app.update();
+        assert_eq!(app.world().resource::<QuickResponsePacing>().poll_interval, Some(Duration::from_millis(250)));
+        assert_matches!(app.world().resource::<WinitSettings>().focused_mode,
+            UpdateMode::Reactive { wait, .. } if wait == Duration::from_millis(250));
+        // unfocused windows are not polled
+        assert_eq!(app.world().resource::<WinitSettings>().unfocused_mode, WinitSettings::desktop_app().unfocused_mode);
+
+        // within the deadzone
+        app.world_mut().write_message(GamepadAxisChangedEvent::new(gamepad, GamepadAxis::LeftStickX, 0.05));
+        app.update();
+        assert!(!app.world().resource::<QuickResponsePacing>().is_boosted());
+
+        app.world_mut().write_message(GamepadAxisChangedEvent::new(gamepad, GamepadAxis::LeftStickX, 0.8));
+        app.update();
+        assert!(app.world().resource::<QuickResponsePacing>().is_boosted());
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::Continuous);
+        assert_eq!(app.world().resource::<QuickResponsePacing>().poll_interval, Some(Duration::from_secs_f64(1.0 / 60.0)));
+
+        app.world_mut().despawn(gamepad);
+        app.update();
+        assert_eq!(app.world().resource::<QuickResponsePacing>().poll_interval, None);
+    }
+
+    #[test]
+    fn test_gamepad_poll_interval() {
+        let now = Instant::now();
+        let settings = ActivityBoostSettings::default();
+
+        assert_eq!(gamepad_poll_interval(&settings, None, now), Some(Duration::from_millis(250)));
+        assert_eq!(gamepad_poll_interval(&settings, Some(now), now + Duration::from_millis(500)), Some(Duration::from_secs_f64(1.0 / 60.0)));
+        // back to slow polling after the grace period
+        assert_eq!(gamepad_poll_interval(&settings, Some(now), now + Duration::from_secs(2)), Some(Duration::from_millis(250)));
+
+        let settings = ActivityBoostSettings { gamepad_idle_poll_interval: Duration::ZERO, ..default() };
+        assert_eq!(gamepad_poll_interval(&settings, None, now), None);
+    }
+
+    #[test]
+    fn test_touch_momentum() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .insert_resource(ActivityBoostSettings {
+                touch_grace_period: Duration::from_millis(10),
+                touch_momentum_period: Duration::from_secs(60),
+                ..default()
+            })
+            .add_plugins(QuickResponsePlugin::power_saving(60.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+
+        let window = app.world_mut().spawn_empty().id();
+        let touch = |phase| TouchInput { phase, position: Vec2::ZERO, window, force: None, id: 0 };
+
+        app.world_mut().write_message(touch(TouchPhase::Started));
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::Continuous);
+
+        std::thread::sleep(Duration::from_millis(50));
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+
+        app.world_mut().write_message(touch(TouchPhase::Ended));
+        app.update();
+        std::thread::sleep(Duration::from_millis(50));
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::Continuous);
+    }
+}
@@ -0,0 +1,115 @@
+use bevy::{asset::{LoadState, UntypedAssetId}, prelude::*};
+
+use crate::QuickResponsePacing;
+
+/// Opt-in: keep the app continuous at max fps (even in `PowerSaving` mode) while tracked assets are loading,
+/// so loading screens and progress bars animate smoothly.
+/// finished (or failed) assets are dropped automatically, and the app returns to the base update mode.
+///
+/// inserted as a resource by [`QuickResponsePlugin`](crate::QuickResponsePlugin).
+#[derive(Resource, Debug, Default, Clone)]
+pub struct AssetLoadingBoost {
+    handles: Vec<UntypedHandle>,
+}
+
+impl AssetLoadingBoost {
+    /// keep the app awake until the asset (and its dependencies) finished loading
+    pub fn track(&mut self, handle: impl Into<UntypedHandle>) {
+        self.handles.push(handle.into());
+    }
+
+    /// stop tracking all assets
+    pub fn clear(&mut self) {
+        self.handles.clear();
+    }
+
+    /// number of tracked assets which are still loading
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+}
+
+fn is_loading(asset_server: &AssetServer, id: UntypedAssetId) -> bool {
+    match asset_server.get_load_state(id) {
+        Some(LoadState::Loading) => true,
+        Some(LoadState::Loaded) => asset_server
+            .get_recursive_dependency_load_state(id)
+            .is_some_and(|state| state.is_loading()),
+        _ => false,
+    }
+}
+
+pub(crate) fn detect_asset_loading(
+    mut boost: ResMut<AssetLoadingBoost>,
+    asset_server: Option<Res<AssetServer>>,
+    mut pacing: ResMut<QuickResponsePacing>,
+) {
+    let Some(asset_server) = asset_server else {
+        return;
+    };
+
+    // only mutate when something has finished, to keep change detection meaningful
+    if boost.handles.iter().any(|handle| !is_loading(&asset_server, handle.id())) {
+        boost.handles.retain(|handle| is_loading(&asset_server, handle.id()));
+    }
+
+    let loading_assets = !boost.is_empty();
+    if pacing.loading_assets != loading_assets {
+        pacing.loading_assets = loading_assets;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::winit::{UpdateMode, WinitSettings};
+
+    use crate::QuickResponsePlugin;
+
+    use super::*;
+
+    #[derive(Asset, TypePath)]
+    struct TestAsset;
+
+    #[test]
+    fn test_asset_loading_boost() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(AssetPlugin::default())
+            .init_asset::<TestAsset>()
+            .add_plugins(QuickResponsePlugin::power_saving(60.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+
+        // no loader is registered, so the load fails after a while
+        let handle: Handle<TestAsset> = app.world().resource::<AssetServer>().load("missing.test");
+        app.world_mut().resource_mut::<AssetLoadingBoost>().track(handle);
+        app.update();
+
+        let mut finished = false;
+        for _ in 0..200 {
+            if app.world().resource::<AssetLoadingBoost>().is_empty() {
+                finished = true;
+                break;
+            }
+            assert!(app.world().resource::<QuickResponsePacing>().loading_assets);
+            assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::Continuous);
+
+            std::thread::sleep(Duration::from_millis(10));
+            app.update();
+        }
+
+        assert!(finished);
+        assert!(!app.world().resource::<QuickResponsePacing>().loading_assets);
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+    }
+}
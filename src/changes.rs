@@ -0,0 +1,150 @@
+use bevy::{prelude::*, window::PresentMode, winit::UpdateMode};
+
+use crate::{QuickResponseMode, QuickResponsePacing};
+
+/// Effective pacing settings, reported by [`QuickResponseChanged`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuickResponseEffectiveSettings {
+    pub mode: QuickResponseMode,
+    pub focused_mode: UpdateMode,
+    pub unfocused_mode: UpdateMode,
+    /// present mode of the primary window
+    pub present_mode: PresentMode,
+    /// max fps for the current monitor (None if unknown or unlimited)
+    pub max_fps: Option<f64>,
+    /// true while the app is boosted (continuous at max fps)
+    pub boosted: bool,
+    /// true while nothing keeps the app awake, and no wake-up is scheduled or polled
+    pub idle: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuickResponseChangeReason {
+    /// the mode was switched (for example: by [`QuickResponseStatePlugin`](crate::QuickResponseStatePlugin))
+    ModeChanged,
+    /// the app started to be boosted (continuous at max fps)
+    BoostStarted,
+    /// the boost ended, and the app returned to the base update mode
+    BoostEnded,
+    /// the mode prefers Mailbox, but this crate uses AutoNoVsync on this platform (chosen at compile time,
+    /// for example: on macOS). reported once at startup.
+    /// NOTE: this is not runtime detection: if the surface does not support the requested present mode,
+    /// wgpu may still fall back to another one without this being reported.
+    PresentModePlatformDefault,
+    /// nothing keeps the app awake anymore, and no wake-up is scheduled or polled
+    IdleEntered,
+}
+
+/// Sent (as a message, and triggered for observers) when pacing changes.
+/// several messages may be sent in the same frame, one for each reason.
+#[derive(Message, Event, Debug, Clone, PartialEq)]
+pub struct QuickResponseChanged {
+    pub reason: QuickResponseChangeReason,
+    pub old: QuickResponseEffectiveSettings,
+    pub new: QuickResponseEffectiveSettings,
+}
+
+/// present mode the mode would use on every platform, if available
+fn preferred_present_mode(mode: QuickResponseMode) -> Option<PresentMode> {
+    match mode {
+        QuickResponseMode::FastVsync(_) => Some(PresentMode::Mailbox),
+        QuickResponseMode::PowerSaving(_) => Some(PresentMode::Mailbox),
+        QuickResponseMode::Immediate(_) => Some(PresentMode::Immediate),
+        QuickResponseMode::AutoNoVsync(_) => Some(PresentMode::AutoNoVsync),
+        QuickResponseMode::Vrr(_) => Some(PresentMode::AutoNoVsync),
+        QuickResponseMode::None(_) => None,
+    }
+}
+
+pub(crate) fn notify_changes(
+    mut commands: Commands,
+    pacing: Res<QuickResponsePacing>,
+    mut changed: MessageWriter<QuickResponseChanged>,
+    mut last: Local<Option<QuickResponseEffectiveSettings>>,
+) {
+    let new = pacing.effective_settings();
+
+    let Some(old) = last.replace(new.clone()) else {
+        // first frame
+        if preferred_present_mode(new.mode).is_some_and(|preferred| preferred != new.present_mode) {
+            let event = QuickResponseChanged {
+                reason: QuickResponseChangeReason::PresentModePlatformDefault,
+                old: new.clone(),
+                new,
+            };
+            changed.write(event.clone());
+            commands.trigger(event);
+        }
+        return;
+    };
+
+    let mut reasons = Vec::new();
+    if old.mode != new.mode {
+        reasons.push(QuickResponseChangeReason::ModeChanged);
+    }
+    if !old.boosted && new.boosted {
+        reasons.push(QuickResponseChangeReason::BoostStarted);
+    }
+    if old.boosted && !new.boosted {
+        reasons.push(QuickResponseChangeReason::BoostEnded);
+    }
+    if !old.idle && new.idle {
+        reasons.push(QuickResponseChangeReason::IdleEntered);
+    }
+
+    for reason in reasons {
+        let event = QuickResponseChanged {
+            reason,
+            old: old.clone(),
+            new: new.clone(),
+        };
+        changed.write(event.clone());
+        commands.trigger(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{KeepAwake, QuickResponsePlugin};
+
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Observed(Vec<QuickResponseChangeReason>);
+
+    #[test]
+    fn test_change_notifications() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(QuickResponsePlugin::auto_no_vsync(60.0, 120.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test())
+            .init_resource::<Observed>()
+            .add_observer(|changed: On<QuickResponseChanged>, mut observed: ResMut<Observed>| {
+                observed.0.push(changed.reason);
+            });
+
+        app.update();
+        let e = app.world_mut().spawn(KeepAwake).id();
+        app.update();
+
+        let messages = app.world().resource::<Messages<QuickResponseChanged>>();
+        let mut cursor = messages.get_cursor();
+        let changed: Vec<_> = cursor.read(messages).cloned().collect();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].reason, QuickResponseChangeReason::BoostStarted);
+        assert!(!changed[0].old.boosted);
+        assert!(changed[0].new.boosted);
+        assert_eq!(changed[0].new.focused_mode, UpdateMode::Continuous);
+
+        app.world_mut().despawn(e);
+        app.update();
+
+        assert_eq!(app.world().resource::<Observed>().0, vec![
+            QuickResponseChangeReason::BoostStarted,
+            QuickResponseChangeReason::BoostEnded,
+            QuickResponseChangeReason::IdleEntered,
+        ]);
+    }
+}
@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use bevy::{platform::time::Instant, prelude::*};
+
+use crate::{QuickResponseMode, QuickResponsePacing};
+
+/// System set (in [`Update`]) which runs at most at base fps, even when the limiter allows max fps.
+/// for expensive bookkeeping which should not scale with the boosted rate.
+/// (`PowerSaving` has no base fps, so 60 fps is used)
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LowFrequency;
+
+/// Run condition: true while the app is boosted (continuous at max fps),
+/// for example: by [`KeepAwake`](crate::KeepAwake) entities or input activity.
+pub fn quick_response_boosted() -> impl FnMut(Option<Res<QuickResponsePacing>>) -> bool + Clone {
+    |pacing: Option<Res<QuickResponsePacing>>| {
+        pacing.is_some_and(|pacing| pacing.is_continuous())
+    }
+}
+
+/// Run condition: true while nothing keeps the app awake, and no wake-up is scheduled or polled
+/// ([`QuickResponsePacing::is_idle`], the same as `idle` of [`QuickResponseEffectiveSettings`](crate::QuickResponseEffectiveSettings)).
+/// unrelated to idle tiers (see [`IdleTier`](crate::IdleTier)).
+pub fn quick_response_idle() -> impl FnMut(Option<Res<QuickResponsePacing>>) -> bool + Clone {
+    |pacing: Option<Res<QuickResponsePacing>>| {
+        pacing.is_some_and(|pacing| pacing.is_idle())
+    }
+}
+
+/// Run condition: true if the current mode is the same kind as the given mode (parameters are ignored).
+///
+/// ```ignore
+/// app.add_systems(Update, my_system.run_if(quick_response_mode_is(QuickResponsePlugin::power_saving(60.0).mode)));
+/// ```
+pub fn quick_response_mode_is(mode: QuickResponseMode) -> impl FnMut(Option<Res<QuickResponsePacing>>) -> bool + Clone {
+    move |pacing: Option<Res<QuickResponsePacing>>| {
+        pacing.is_some_and(|pacing| std::mem::discriminant(&pacing.mode) == std::mem::discriminant(&mode))
+    }
+}
+
+pub(crate) fn low_frequency_interval(mode: QuickResponseMode) -> Duration {
+    match mode {
+        QuickResponseMode::FastVsync(params) => Duration::from_secs_f64(1.0 / params.base_fps),
+        QuickResponseMode::AutoNoVsync(params) => Duration::from_secs_f64(1.0 / params.base_fps),
+        QuickResponseMode::Vrr(params) => Duration::from_secs_f64(1.0 / params.base_fps),
+        QuickResponseMode::Immediate(params) => Duration::from_secs_f64(1.0 / params.base_fps),
+        QuickResponseMode::PowerSaving(_) => Duration::from_secs_f64(1.0 / 60.0),
+        QuickResponseMode::None(_) => Duration::ZERO,
+    }
+}
+
+/// true if `interval` has passed since the last run (with a small tolerance, so that frames paced exactly
+/// at base fps are not skipped by jitter). the last run advances by whole intervals, so the tolerance and
+/// the time left over are carried to the next run, and the average rate does not exceed the base fps.
+/// after a longer gap (for example: a sleep), it restarts from `now` instead of catching up.
+fn advance_low_frequency(last_run: &mut Option<Instant>, now: Instant, interval: Duration) -> bool {
+    let Some(prev) = *last_run else {
+        *last_run = Some(now);
+        return true;
+    };
+    if now.saturating_duration_since(prev) < interval.mul_f64(0.9) {
+        return false;
+    }
+
+    let next = prev + interval;
+    *last_run = Some(if now.saturating_duration_since(next) >= interval { now } else { next });
+    true
+}
+
+pub(crate) fn low_frequency_due(
+    pacing: Option<Res<QuickResponsePacing>>,
+    mut last_run: Local<Option<Instant>>,
+) -> bool {
+    let interval = pacing.map_or(Duration::ZERO, |pacing| low_frequency_interval(pacing.mode));
+    advance_low_frequency(&mut last_run, Instant::now(), interval)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{KeepAwake, QuickResponsePlugin, WakeSchedule};
+
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Counter {
+        boosted: u32,
+        idle: u32,
+        power_saving: u32,
+        low_frequency: u32,
+    }
+
+    #[test]
+    fn test_advance_low_frequency() {
+        let now = Instant::now();
+        let ms = Duration::from_millis;
+        let mut last_run = None;
+
+        assert!(advance_low_frequency(&mut last_run, now, ms(10)));
+        assert!(!advance_low_frequency(&mut last_run, now + ms(8), ms(10)));
+        // slightly early by jitter
+        assert!(advance_low_frequency(&mut last_run, now + ms(9), ms(10)));
+        // the early part is carried over, so the next run is not due before 19ms
+        assert!(!advance_low_frequency(&mut last_run, now + ms(17), ms(10)));
+        assert!(advance_low_frequency(&mut last_run, now + ms(23), ms(10)));
+        // the late part is carried over too
+        assert!(advance_low_frequency(&mut last_run, now + ms(29), ms(10)));
+        // restarts after a long gap, instead of catching up
+        assert!(advance_low_frequency(&mut last_run, now + ms(100), ms(10)));
+        assert!(!advance_low_frequency(&mut last_run, now + ms(105), ms(10)));
+        assert_eq!(last_run, Some(now + ms(100)));
+    }
+
+    #[test]
+    fn test_run_conditions() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(QuickResponsePlugin::fast_vsync(1.0, 120.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test())
+            .init_resource::<Counter>()
+            .add_systems(Update, (
+                (|mut c: ResMut<Counter>| c.boosted += 1).run_if(quick_response_boosted()),
+                (|mut c: ResMut<Counter>| c.idle += 1).run_if(quick_response_idle()),
+                (|mut c: ResMut<Counter>| c.power_saving += 1).run_if(quick_response_mode_is(QuickResponsePlugin::power_saving(60.0).mode)),
+                (|mut c: ResMut<Counter>| c.low_frequency += 1).in_set(LowFrequency),
+            ));
+
+        app.update();
+        let keep_awake = app.world_mut().spawn(KeepAwake).id();
+        // pacing is updated at the end of the frame, so the condition changes from the next frame
+        app.update();
+        app.update();
+
+        let counter = app.world().resource::<Counter>();
+        assert_eq!(counter.idle, 2);
+        assert_eq!(counter.boosted, 1);
+        assert_eq!(counter.power_saving, 0);
+        // base fps is 1, so only the first frame runs
+        assert_eq!(counter.low_frequency, 1);
+
+        // not boosted, but a wake-up is scheduled: not idle
+        app.world_mut().despawn(keep_awake);
+        app.world_mut().resource_mut::<WakeSchedule>().wake_after(Duration::from_secs(60));
+        app.update();
+        app.update();
+        let counter = app.world().resource::<Counter>();
+        assert_eq!(counter.idle, 2);
+        assert_eq!(counter.boosted, 2);
+    }
+}
@@ -0,0 +1,78 @@
+use bevy::{prelude::*, window::{CursorGrabMode, CursorOptions, PrimaryWindow}};
+
+use crate::{QuickResponsePacing, is_competitive_on_cursor_grab};
+
+pub(crate) fn detect_cursor_grab(
+    cursor_options: Query<&CursorOptions, With<PrimaryWindow>>,
+    mut pacing: ResMut<QuickResponsePacing>,
+) {
+    let cursor_grabbed = is_competitive_on_cursor_grab(pacing.mode) && cursor_options.single()
+        .is_ok_and(|cursor_options| cursor_options.grab_mode == CursorGrabMode::Locked);
+
+    if pacing.cursor_grabbed != cursor_grabbed {
+        pacing.cursor_grabbed = cursor_grabbed;
+    }
+}
+
+/// switch the present mode of the primary window only on grab / release,
+/// so that present mode changes made by the user in between are kept.
+pub(crate) fn apply_cursor_grab_present_mode(
+    pacing: Res<QuickResponsePacing>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut was_grabbed: Local<bool>,
+) {
+    if pacing.cursor_grabbed == *was_grabbed {
+        return;
+    }
+
+    let Ok(mut window) = windows.single_mut() else {
+        return;
+    };
+    *was_grabbed = pacing.cursor_grabbed;
+
+    window.present_mode = pacing.present_mode();
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{window::PresentMode, winit::{UpdateMode, WinitSettings}};
+
+    use crate::{QuickResponsePlugin, QuickResponseParametersWithNoBaseFps, QuickResponseMode};
+
+    use super::*;
+
+    #[test]
+    fn test_cursor_grab_competitive() {
+        let pl = QuickResponsePlugin::new(QuickResponseMode::PowerSaving(QuickResponseParametersWithNoBaseFps {
+            competitive_on_cursor_grab: true,
+            ..default()
+        }))
+        .with_no_default_plugins()
+        .with_no_framepace_for_test();
+
+        let base_present_mode = pl.window_plugin().primary_window.unwrap().present_mode;
+
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(pl);
+
+        let window = app.world_mut().spawn((
+            Window { present_mode: base_present_mode, ..default() },
+            CursorOptions::default(),
+            PrimaryWindow,
+        )).id();
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+
+        app.world_mut().get_mut::<CursorOptions>(window).unwrap().grab_mode = CursorGrabMode::Locked;
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::Continuous);
+        assert_eq!(app.world().get::<Window>(window).unwrap().present_mode, PresentMode::Immediate);
+
+        app.world_mut().get_mut::<CursorOptions>(window).unwrap().grab_mode = CursorGrabMode::None;
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+        assert_eq!(app.world().get::<Window>(window).unwrap().present_mode, base_present_mode);
+    }
+}
@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use bevy::{platform::time::Instant, prelude::*};
+
+use crate::QuickResponsePacing;
+
+/// How the delta of [`Time<Virtual>`] is treated after a pacing transition
+/// (for example: dropping from max fps to base fps, or waking up from a long sleep in `PowerSaving` mode).
+///
+/// the delta is limited through [`Time::<Virtual>::set_max_delta`], so the virtual clock just falls behind the real clock.
+/// the max delta set by the user is kept as the upper bound.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DeltaSmoothing {
+    /// use the raw delta (bevy default behavior)
+    #[default]
+    Off,
+    /// clamp the delta of a frame which followed a sleep to the given duration.
+    /// `Duration::ZERO` disables the clamp (a zero delta would stop the virtual clock).
+    Clamp(Duration),
+    /// limit the delta to grow at most by the given factor per frame (for example: 1.5).
+    /// the rest of a spike is dropped (the virtual clock falls behind the real clock), not carried over to later frames.
+    /// factors below 1.0 are treated as 1.0. NaN, infinite or too large factors disable the limit.
+    Smooth(f64),
+}
+
+#[derive(Default)]
+pub(crate) struct DeltaSmoothingState {
+    /// max delta configured by the user (or bevy default)
+    user_max_delta: Duration,
+    /// max delta set by this plugin on the previous frame
+    applied_max_delta: Option<Duration>,
+    /// effective (unscaled) delta of the previous frame
+    prev_delta: Duration,
+}
+
+#[derive(Resource, Debug, Clone, Copy)]
+pub(crate) struct DeltaSmoothingSettings {
+    pub smoothing: DeltaSmoothing,
+    /// a frame interval longer than this is regarded as a sleep
+    pub sleep_threshold: Duration,
+}
+
+/// the largest delta after `prev_delta` for the growth factor (None for no limit)
+fn smooth_limit(prev_delta: Duration, max_growth: f64) -> Option<Duration> {
+    if prev_delta.is_zero() || max_growth.is_nan() {
+        return None;
+    }
+    Duration::try_from_secs_f64(prev_delta.as_secs_f64() * max_growth.max(1.0)).ok()
+}
+
+/// runs in [`First`] before [`TimeSystems`](bevy::time::TimeSystems), so that the limit applies to the upcoming delta.
+pub(crate) fn smooth_delta(
+    settings: Res<DeltaSmoothingSettings>,
+    real: Res<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut pacing: ResMut<QuickResponsePacing>,
+    mut state: Local<DeltaSmoothingState>,
+) {
+    let Some(last_update) = real.last_update() else {
+        return;
+    };
+    let raw_delta = Instant::now().saturating_duration_since(last_update);
+
+    let after_sleep = raw_delta > settings.sleep_threshold;
+    if pacing.after_sleep != after_sleep {
+        pacing.after_sleep = after_sleep;
+    }
+
+    // adopt the max delta if it was changed by someone else
+    if state.applied_max_delta != Some(virtual_time.max_delta()) {
+        state.user_max_delta = virtual_time.max_delta();
+    }
+
+    let limit = match settings.smoothing {
+        DeltaSmoothing::Off => None,
+        DeltaSmoothing::Clamp(clamp) => after_sleep.then_some(clamp),
+        DeltaSmoothing::Smooth(max_growth) => smooth_limit(state.prev_delta, max_growth),
+    };
+
+    let max_delta = match limit {
+        Some(limit) if !limit.is_zero() => limit.min(state.user_max_delta),
+        _ => state.user_max_delta,
+    };
+
+    if virtual_time.max_delta() != max_delta {
+        virtual_time.set_max_delta(max_delta);
+    }
+    state.applied_max_delta = Some(max_delta);
+    state.prev_delta = raw_delta.min(max_delta);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{QuickResponseMode, QuickResponseParametersWithNoBaseFps, QuickResponsePlugin};
+
+    use super::*;
+
+    #[test]
+    fn test_delta_smoothing_clamp() {
+        let clamp = Duration::from_millis(10);
+
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(QuickResponsePlugin::new(QuickResponseMode::PowerSaving(QuickResponseParametersWithNoBaseFps {
+                max_fps: 1000.0.into(),
+                auto_init_default_plugins: false,
+                delta_smoothing: DeltaSmoothing::Clamp(clamp),
+                ..default()
+            }))
+            .with_no_framepace_for_test());
+
+        app.update();
+        app.update();
+
+        std::thread::sleep(Duration::from_millis(50));
+        app.update();
+
+        assert!(app.world().resource::<QuickResponsePacing>().after_sleep);
+        assert!(app.world().resource::<Time<Virtual>>().delta() <= clamp);
+        assert!(app.world().resource::<Time<Real>>().delta() > clamp);
+    }
+
+    #[test]
+    fn test_smooth_limit() {
+        let prev = Duration::from_millis(10);
+        assert_eq!(smooth_limit(prev, 1.5), Some(Duration::from_millis(15)));
+        assert_eq!(smooth_limit(prev, 0.5), Some(prev));
+        assert_eq!(smooth_limit(Duration::ZERO, 1.5), None);
+        // no panic, no limit
+        assert_eq!(smooth_limit(prev, f64::INFINITY), None);
+        assert_eq!(smooth_limit(prev, f64::NAN), None);
+        assert_eq!(smooth_limit(prev, 1e300), None);
+    }
+
+    #[test]
+    fn test_delta_smoothing_keeps_user_max_delta() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(QuickResponsePlugin::power_saving(1000.0)
+                .with_delta_smoothing(DeltaSmoothing::Clamp(Duration::from_secs(10)))
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+
+        app.world_mut().resource_mut::<Time<Virtual>>().set_max_delta(Duration::from_millis(5));
+        app.update();
+        std::thread::sleep(Duration::from_millis(20));
+        app.update();
+
+        assert_eq!(app.world().resource::<Time<Virtual>>().max_delta(), Duration::from_millis(5));
+        assert!(app.world().resource::<Time<Virtual>>().delta() <= Duration::from_millis(5));
+    }
+}
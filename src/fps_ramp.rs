@@ -0,0 +1,214 @@
+use std::time::Duration;
+
+use bevy::{platform::time::Instant, prelude::*};
+
+use crate::{QuickResponsePacing, base_fps_for_mode, fps_ramp_for_mode, input_messages::InputMessages};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FpsRampCurve {
+    /// the fps decreases by the same amount every second
+    #[default]
+    Linear,
+    /// the fps decreases by the same ratio every second (fast at first, slow near the base fps)
+    Exponential,
+}
+
+/// Ramp of the frame limiter from max fps down to base fps when the app leaves a boosted state,
+/// instead of jumping straight to base fps.
+/// the app keeps updating continuously during the ramp, so it ends at [`FpsRamp::MIN_FPS`] at the lowest
+/// (then the app returns to the base update mode). modes without base fps (`PowerSaving`) do not ramp.
+///
+/// the app is held at max fps for `hysteresis` first, so brief input gaps do not cause oscillation.
+/// input (keyboard, mouse, touch, gamepad buttons) during the ramp restarts it from max fps (with the hysteresis).
+///
+/// ```ignore
+/// app.add_plugins(QuickResponsePlugin::fast_vsync(60.0, 120.0)
+///     .with_fps_ramp(FpsRamp::exponential(Duration::from_millis(500)).with_hysteresis(Duration::from_millis(200))));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FpsRamp {
+    pub curve: FpsRampCurve,
+    /// time from max fps to base fps (after the hysteresis)
+    pub duration: Duration,
+    /// time held at max fps before the ramp starts.
+    /// default: 100ms
+    pub hysteresis: Duration,
+}
+
+impl FpsRamp {
+    /// lowest fps of a ramp, so that input is not left waiting for a long frame at the end of the ramp
+    pub const MIN_FPS: f64 = 20.0;
+
+    pub fn linear(duration: Duration) -> Self {
+        FpsRamp {
+            curve: FpsRampCurve::Linear,
+            duration,
+            hysteresis: Duration::from_millis(100),
+        }
+    }
+
+    pub fn exponential(duration: Duration) -> Self {
+        FpsRamp {
+            curve: FpsRampCurve::Exponential,
+            duration,
+            hysteresis: Duration::from_millis(100),
+        }
+    }
+
+    pub fn with_hysteresis(&self, hysteresis: Duration) -> Self {
+        FpsRamp { hysteresis, ..*self }
+    }
+
+    /// fps at `elapsed` since the boost ended (None when the ramp is over)
+    pub fn fps(&self, max_fps: f64, base_fps: f64, elapsed: Duration) -> Option<f64> {
+        if elapsed < self.hysteresis {
+            return Some(max_fps);
+        }
+        let t = elapsed - self.hysteresis;
+        if t >= self.duration {
+            return None;
+        }
+
+        let progress = t.as_secs_f64() / self.duration.as_secs_f64();
+        Some(match self.curve {
+            FpsRampCurve::Linear => max_fps + (base_fps - max_fps) * progress,
+            FpsRampCurve::Exponential => max_fps * (base_fps / max_fps).powf(progress),
+        })
+    }
+}
+
+/// end of the last boost, to follow the ramp
+#[derive(Debug, Default)]
+pub(crate) struct RampTracker {
+    boost_ended: Option<Instant>,
+}
+
+impl RampTracker {
+    /// fps of the ramp (None while boosted, or when there is nothing to ramp)
+    fn update(&mut self, now: Instant, boosted: bool, input: bool, ramp: Option<FpsRamp>, max_fps: Option<f64>, base_fps: Option<f64>) -> Option<f64> {
+        if boosted {
+            self.boost_ended = Some(now);
+            return None;
+        }
+        // restart the ramp from max fps, so that input is not throttled by the ramp
+        if input && self.boost_ended.is_some() {
+            self.boost_ended = Some(now);
+        }
+
+        let base_fps = base_fps.map(|base_fps| base_fps.max(FpsRamp::MIN_FPS));
+        let ramp_fps = match (ramp, self.boost_ended, max_fps, base_fps) {
+            (Some(ramp), Some(ended), Some(max_fps), Some(base_fps)) if base_fps < max_fps => {
+                ramp.fps(max_fps, base_fps, now.saturating_duration_since(ended))
+            }
+            _ => None,
+        };
+        if ramp_fps.is_none() {
+            self.boost_ended = None;
+        }
+        ramp_fps
+    }
+}
+
+pub(crate) fn detect_fps_ramp(
+    mut inputs: InputMessages,
+    mut pacing: ResMut<QuickResponsePacing>,
+    mut tracker: Local<RampTracker>,
+) {
+    let input = !inputs.read_kinds().is_empty();
+    let ramp_fps = tracker.update(
+        Instant::now(),
+        pacing.is_awake_requested(),
+        input,
+        fps_ramp_for_mode(pacing.mode),
+        pacing.effective_max_fps(),
+        base_fps_for_mode(pacing.mode),
+    );
+    if pacing.ramp_fps != ramp_fps {
+        pacing.ramp_fps = ramp_fps;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::winit::{UpdateMode, WinitSettings};
+    use bevy_framepace::{FramepaceSettings, Limiter};
+
+    use crate::{KeepAwake, QuickResponseChangeReason, QuickResponseChanged, QuickResponsePlugin};
+
+    use super::*;
+
+    #[test]
+    fn test_ramp_curves() {
+        let ramp = FpsRamp::linear(Duration::from_millis(100)).with_hysteresis(Duration::from_millis(50));
+        assert_eq!(ramp.fps(120.0, 60.0, Duration::from_millis(20)), Some(120.0));
+        assert_eq!(ramp.fps(120.0, 60.0, Duration::from_millis(100)), Some(90.0));
+        assert_eq!(ramp.fps(120.0, 60.0, Duration::from_millis(150)), None);
+
+        let ramp = FpsRamp::exponential(Duration::from_millis(100)).with_hysteresis(Duration::ZERO);
+        assert_matches!(ramp.fps(120.0, 30.0, Duration::from_millis(50)), Some(fps) if (fps - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ramp_tracker() {
+        let now = Instant::now();
+        let ms = Duration::from_millis;
+        let ramp = Some(FpsRamp::linear(ms(200)).with_hysteresis(ms(30)));
+        let mut tracker = RampTracker::default();
+
+        assert_eq!(tracker.update(now, true, false, ramp, Some(120.0), Some(60.0)), None);
+        // held at max fps by the hysteresis
+        assert_eq!(tracker.update(now + ms(10), false, false, ramp, Some(120.0), Some(60.0)), Some(120.0));
+        assert_eq!(tracker.update(now + ms(130), false, false, ramp, Some(120.0), Some(60.0)), Some(90.0));
+        assert_eq!(tracker.update(now + ms(230), false, false, ramp, Some(120.0), Some(60.0)), None);
+        // stays at base fps after the ramp
+        assert_eq!(tracker.update(now + ms(240), false, false, ramp, Some(120.0), Some(60.0)), None);
+
+        // input during the ramp restarts it
+        let mut tracker = RampTracker::default();
+        tracker.update(now, true, false, ramp, Some(120.0), Some(60.0));
+        assert_eq!(tracker.update(now + ms(130), false, true, ramp, Some(120.0), Some(60.0)), Some(120.0));
+        assert_eq!(tracker.update(now + ms(260), false, false, ramp, Some(120.0), Some(60.0)), Some(90.0));
+
+        // ends at the minimum fps
+        let mut tracker = RampTracker::default();
+        tracker.update(now, true, false, ramp, Some(120.0), Some(1.0));
+        assert_eq!(tracker.update(now + ms(130), false, false, ramp, Some(120.0), Some(1.0)), Some((120.0 + FpsRamp::MIN_FPS) / 2.0));
+
+        // nothing to ramp: unknown max fps, or no base fps
+        let mut tracker = RampTracker::default();
+        tracker.update(now, true, false, ramp, Some(120.0), Some(60.0));
+        assert_eq!(tracker.update(now + ms(10), false, false, ramp, None, Some(60.0)), None);
+        tracker.update(now, true, false, ramp, Some(120.0), None);
+        assert_eq!(tracker.update(now + ms(10), false, false, ramp, Some(120.0), None), None);
+    }
+
+    #[test]
+    fn test_fps_ramp() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .init_resource::<FramepaceSettings>()
+            .add_plugins(QuickResponsePlugin::fast_vsync(60.0, 120.0)
+                .with_fps_ramp(FpsRamp::linear(Duration::from_secs(1)).with_hysteresis(Duration::from_secs(60)))
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+
+        let keep_awake = app.world_mut().spawn(KeepAwake).id();
+        app.update();
+        app.world_mut().despawn(keep_awake);
+
+        // held at max fps by the hysteresis
+        app.update();
+        assert_eq!(app.world().resource::<QuickResponsePacing>().ramp_fps, Some(120.0));
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::Continuous);
+        assert_matches!(app.world().resource::<FramepaceSettings>().limiter,
+            Limiter::Manual(d) if d == Duration::from_secs_f64(1.0 / 120.0));
+
+        // still boosted while ramping: the boost has not ended
+        let pacing = app.world().resource::<QuickResponsePacing>();
+        assert!(pacing.effective_settings().boosted);
+        assert!(!pacing.is_idle());
+        let messages = app.world().resource::<Messages<QuickResponseChanged>>();
+        assert!(messages.get_cursor().read(messages).all(|changed| changed.reason == QuickResponseChangeReason::BoostStarted));
+    }
+}
@@ -0,0 +1,141 @@
+use std::time::Duration;
+
+use bevy::{platform::time::Instant, prelude::*};
+
+use crate::{ActivityBoostSettings, QuickResponsePacing, input_messages::InputMessages};
+
+/// An idle ("screensaver") tier: after `after` without input, update at `fps`,
+/// or stop updating until the next input if `fps` is None.
+/// the next input snaps back to boosted (see [`ActivityBoostSettings::idle_exit_boost`]).
+///
+/// tiers are set by [`ActivityBoostSettings::idle_tiers`]:
+///
+/// ```ignore
+/// app.insert_resource(ActivityBoostSettings {
+///     idle_tiers: vec![
+///         IdleTier { after: Duration::from_secs(60), fps: Some(2.0) },
+///         IdleTier { after: Duration::from_secs(600), fps: None },
+///     ],
+///     ..default()
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IdleTier {
+    pub after: Duration,
+    pub fps: Option<f64>,
+}
+
+impl IdleTier {
+    /// longest wait of an idle tier, also used for "stop updating" (`fps: None`).
+    /// finite like the unfocused wait of `WinitSettings::desktop_app()`, because winit keeps
+    /// waking up immediately if the wait cannot be added to the current instant.
+    pub const MAX_WAIT: Duration = Duration::from_secs(60);
+
+    /// wait of the reactive update mode in this tier (at most [`IdleTier::MAX_WAIT`])
+    pub fn wait(&self) -> Duration {
+        self.fps
+            .filter(|fps| *fps > 0.0)
+            .and_then(|fps| Duration::try_from_secs_f64(1.0 / fps).ok())
+            .map_or(Self::MAX_WAIT, |wait| wait.min(Self::MAX_WAIT))
+    }
+}
+
+/// time of the last input, to pick the active tier
+#[derive(Debug, Default)]
+pub(crate) struct IdleTracker {
+    last_input: Option<Instant>,
+}
+
+impl IdleTracker {
+    /// the active tier (the one with the longest `after` which has passed since the last input).
+    /// boosted time (for example: KeepAwake) is not regarded as inactive.
+    fn update(&mut self, now: Instant, active: bool, tiers: &[IdleTier]) -> Option<IdleTier> {
+        if active || self.last_input.is_none() {
+            self.last_input = Some(now);
+        }
+
+        let inactive = self.last_input.map_or(Duration::ZERO, |last_input| now.saturating_duration_since(last_input));
+        tiers.iter()
+            .filter(|tier| inactive >= tier.after)
+            .max_by_key(|tier| tier.after)
+            .copied()
+    }
+}
+
+pub(crate) fn detect_idle_tier(
+    settings: Res<ActivityBoostSettings>,
+    mut inputs: InputMessages,
+    mut pacing: ResMut<QuickResponsePacing>,
+    mut tracker: Local<IdleTracker>,
+) {
+    let input = !inputs.read_kinds().is_empty();
+
+    if input && pacing.idle_tier.is_some() && !settings.idle_exit_boost.is_zero() {
+        pacing.request_boost(settings.idle_exit_boost);
+    }
+
+    let active = input || pacing.is_continuous();
+    let idle_tier = tracker.update(Instant::now(), active, &settings.idle_tiers);
+    if pacing.idle_tier != idle_tier {
+        pacing.idle_tier = idle_tier;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{window::CursorMoved, winit::{UpdateMode, WinitSettings}};
+
+    use crate::QuickResponsePlugin;
+
+    use super::*;
+
+    const IDLE_TIERS: &[IdleTier] = &[
+        IdleTier { after: Duration::from_millis(20), fps: Some(2.0) },
+        IdleTier { after: Duration::from_millis(60), fps: None },
+    ];
+
+    #[test]
+    fn test_idle_tracker() {
+        let now = Instant::now();
+        let mut tracker = IdleTracker::default();
+
+        assert_eq!(tracker.update(now, false, IDLE_TIERS), None);
+        assert_eq!(tracker.update(now + Duration::from_millis(30), false, IDLE_TIERS), Some(IDLE_TIERS[0]));
+        assert_eq!(tracker.update(now + Duration::from_millis(70), false, IDLE_TIERS), Some(IDLE_TIERS[1]));
+
+        // input restarts the inactive time
+        assert_eq!(tracker.update(now + Duration::from_millis(80), true, IDLE_TIERS), None);
+        assert_eq!(tracker.update(now + Duration::from_millis(110), false, IDLE_TIERS), Some(IDLE_TIERS[0]));
+    }
+
+    #[test]
+    fn test_idle_tier_wait() {
+        assert_eq!(IDLE_TIERS[0].wait(), Duration::from_millis(500));
+        // "stop updating" still has a finite wait, so winit does not wake up immediately
+        assert_eq!(IDLE_TIERS[1].wait(), IdleTier::MAX_WAIT);
+        assert_eq!(IdleTier { after: Duration::ZERO, fps: Some(1e-300) }.wait(), IdleTier::MAX_WAIT);
+    }
+
+    #[test]
+    fn test_idle_tiers() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .insert_resource(ActivityBoostSettings {
+                idle_tiers: vec![IdleTier { after: Duration::ZERO, fps: None }],
+                ..default()
+            })
+            .add_plugins(QuickResponsePlugin::fast_vsync(60.0, 120.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::reactive_low_power(IdleTier::MAX_WAIT));
+
+        // snap back on input
+        let window = app.world_mut().spawn_empty().id();
+        app.world_mut().write_message(CursorMoved { window, position: Vec2::ZERO, delta: None });
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::Continuous);
+    }
+}
@@ -0,0 +1,30 @@
+use bevy::{ecs::system::SystemParam, input::{gamepad::GamepadButtonChangedEvent, keyboard::KeyboardInput, mouse::{MouseButtonInput, MouseWheel}}, prelude::*, window::CursorMoved};
+
+/// readers of the input messages of a frame (each system using this has its own cursors)
+#[derive(SystemParam)]
+pub(crate) struct InputMessages<'w, 's> {
+    keys: MessageReader<'w, 's, KeyboardInput>,
+    mouse_buttons: MessageReader<'w, 's, MouseButtonInput>,
+    mouse_wheels: MessageReader<'w, 's, MouseWheel>,
+    cursors: MessageReader<'w, 's, CursorMoved>,
+    touches: MessageReader<'w, 's, TouchInput>,
+    gamepad_buttons: MessageReader<'w, 's, GamepadButtonChangedEvent>,
+}
+
+impl InputMessages<'_, '_> {
+    /// kinds of input received since the last call
+    /// (all messages are read, so that old messages are not seen again)
+    pub(crate) fn read_kinds(&mut self) -> Vec<&'static str> {
+        [
+            ("keyboard", self.keys.read().count()),
+            ("mouse button", self.mouse_buttons.read().count()),
+            ("mouse wheel", self.mouse_wheels.read().count()),
+            ("cursor moved", self.cursors.read().count()),
+            ("touch", self.touches.read().count()),
+            ("gamepad button", self.gamepad_buttons.read().count()),
+        ].into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(kind, _)| kind)
+            .collect()
+    }
+}
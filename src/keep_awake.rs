@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+
+use crate::QuickResponsePacing;
+
+/// While any entity with this component exists (for example: a playing animation, a loading spinner),
+/// the focused update mode is kept continuous at max fps.
+/// when the last one is removed, the app returns to the base (reactive) update mode.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct KeepAwake;
+
+pub(crate) fn detect_keep_awake(
+    keep_awake: Query<(), With<KeepAwake>>,
+    mut pacing: ResMut<QuickResponsePacing>,
+) {
+    let is_awake = !keep_awake.is_empty();
+
+    if pacing.keep_awake != is_awake {
+        pacing.keep_awake = is_awake;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::winit::{UpdateMode, WinitSettings};
+
+    use crate::QuickResponsePlugin;
+
+    use super::*;
+
+    #[test]
+    fn test_keep_awake() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(QuickResponsePlugin::fast_vsync(30.0, 120.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+
+        let base_mode = UpdateMode::reactive_low_power(Duration::from_secs_f64(1.0 / 30.0));
+
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, base_mode);
+
+        let a = app.world_mut().spawn(KeepAwake).id();
+        let b = app.world_mut().spawn(KeepAwake).id();
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::Continuous);
+        assert_eq!(app.world().resource::<WinitSettings>().unfocused_mode, base_mode);
+
+        app.world_mut().despawn(a);
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::Continuous);
+
+        app.world_mut().despawn(b);
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, base_mode);
+    }
+
+    #[test]
+    fn test_keep_awake_power_saving() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(QuickResponsePlugin::power_saving(60.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+
+        let e = app.world_mut().spawn(KeepAwake).id();
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::Continuous);
+
+        app.world_mut().entity_mut(e).remove::<KeepAwake>();
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+    }
+}
@@ -1,9 +1,54 @@
 use std::time::Duration;
 
 use bevy::{prelude::*, winit::{UpdateMode, WinitSettings}};
-use bevy::window::{Window, WindowPlugin};
+use bevy::input::gamepad::{GamepadAxisChangedEvent, GamepadButtonChangedEvent, GamepadConnectionEvent};
+use bevy::input::{keyboard::KeyboardInput, mouse::{MouseButtonInput, MouseMotion, MouseWheel}};
+use bevy::time::TimeSystems;
+use bevy::window::{CursorMoved, PresentMode, RequestRedraw, Window, WindowEvent, WindowMoved, WindowPlugin, WindowResized};
 use bevy_framepace::{FramepacePlugin, FramepaceSettings, Limiter};
 
+mod pacing;
+mod keep_awake;
+mod waker;
+mod wake_schedule;
+mod delta_smoothing;
+mod asset_loading;
+mod activity;
+mod cursor_grab;
+mod state_profiles;
+mod conditions;
+mod changes;
+mod plugin_group;
+mod winit_policy;
+mod max_fps;
+mod stats;
+mod recorder;
+mod trace;
+mod input_messages;
+mod wake_reason;
+mod idle_tiers;
+mod fps_ramp;
+
+pub use pacing::{QuickResponsePacing, QuickResponseSystems};
+pub use keep_awake::KeepAwake;
+pub use waker::QuickResponseWaker;
+pub use wake_schedule::WakeSchedule;
+pub use delta_smoothing::DeltaSmoothing;
+pub use asset_loading::AssetLoadingBoost;
+pub use activity::ActivityBoostSettings;
+pub use state_profiles::QuickResponseStatePlugin;
+pub use changes::{QuickResponseChangeReason, QuickResponseChanged, QuickResponseEffectiveSettings};
+pub use plugin_group::QuickResponsePlugins;
+pub use winit_policy::WinitSettingsPolicy;
+pub use max_fps::MaxFps;
+pub use stats::FramePacingStats;
+pub use recorder::{FrameRecord, FrameRecordFormat, FrameRecorder};
+pub use trace::PacingTrace;
+pub use wake_reason::{WakeReason, WakeReasonCounts};
+pub use idle_tiers::IdleTier;
+pub use fps_ramp::{FpsRamp, FpsRampCurve};
+pub use conditions::{LowFrequency, quick_response_boosted, quick_response_idle, quick_response_mode_is};
+
 pub struct QuickResponsePlugin {
     pub mode: QuickResponseMode,
     /// if true, do not add the bevy_framepace::FramepacePlugin
@@ -20,6 +65,11 @@ pub enum QuickResponseMode {
     /// use auto no vsync for all platforms
     /// recommended if you want to work with multiple platforms, but may cause flickering
     AutoNoVsync (QuickResponseParameters),
+    /// for VRR (FreeSync / G-Sync) displays: use auto no vsync (Immediate if supported, tearing is hidden by VRR),
+    /// and cap max fps a few fps below the refresh rate of the monitor (see [`QuickResponsePlugin::vrr`]).
+    /// max_fps should be `MaxFps::BelowMonitor(margin)`.
+    /// until the monitor is known, max fps is capped below [`MaxFps::FALLBACK_REFRESH_RATE`] (60Hz) instead.
+    Vrr (QuickResponseParameters),
     /// Power saving mode: choose FastVsync for presentation, and use desktop app settings for winit
     /// NOT recommended for games, but recommended for desktop apps.
     PowerSaving (QuickResponseParametersWithNoBaseFps),
@@ -41,33 +91,119 @@ pub struct QuickResponseParameters {
     /// default: 60.0
     pub base_fps: f64,
     /// max fps, for example: when mouse moves over window.
-    /// default: 120.0
-    pub max_fps: f64,
+    /// default: MaxFps::Fixed(120.0)
+    pub max_fps: MaxFps,
     /// auto initialize default plugins (DefaultPlugins, and WindowPlugin in it).
     /// default: true
-    pub auto_init_default_plugins: bool
+    pub auto_init_default_plugins: bool,
+    /// clamp or smooth `Time<Virtual>` delta after a pacing transition (for example: after a sleep).
+    /// default: DeltaSmoothing::Off
+    pub delta_smoothing: DeltaSmoothing,
+    /// while the cursor of the primary window is locked (`CursorGrabMode::Locked`), use Immediate present mode
+    /// and update continuously at max fps. reverts to the configured mode when released.
+    /// NOTE: older DX12 and Wayland may not support Immediate mode (may cause panic)
+    /// default: false
+    pub competitive_on_cursor_grab: bool,
+    /// how to treat `WinitSettings` which already exist when the plugin is added.
+    /// default: WinitSettingsPolicy::Overwrite
+    pub winit_settings_policy: WinitSettingsPolicy,
+    /// snap max fps to the nearest divisor or multiple of the monitor refresh rate, to avoid judder
+    /// (for example: 120 on a 144Hz monitor becomes 144). the chosen value is reported by
+    /// [`QuickResponsePacing::effective_max_fps`].
+    /// default: false
+    pub quantize_max_fps: bool,
+    /// ramp the frame limiter down from max fps to base fps when leaving a boosted state (see [`FpsRamp`]).
+    /// default: None (jump straight to base fps)
+    pub fps_ramp: Option<FpsRamp>,
 }
 
 #[derive(Debug, Clone, PartialEq, Copy)]
 pub struct QuickResponseParametersWithNoBaseFps {
     /// max fps, for example: when mouse moves over window.
-    /// default: 120.0
-    pub max_fps: f64,
+    /// default: MaxFps::Fixed(120.0)
+    pub max_fps: MaxFps,
     /// auto initialize default plugins (DefaultPlugins, and WindowPlugin in it).
     /// default: true
-    pub auto_init_default_plugins: bool
+    pub auto_init_default_plugins: bool,
+    /// clamp or smooth `Time<Virtual>` delta after a pacing transition (for example: after a sleep).
+    /// default: DeltaSmoothing::Off
+    pub delta_smoothing: DeltaSmoothing,
+    /// while the cursor of the primary window is locked (`CursorGrabMode::Locked`), use Immediate present mode
+    /// and update continuously at max fps. reverts to the configured mode when released.
+    /// NOTE: older DX12 and Wayland may not support Immediate mode (may cause panic)
+    /// default: false
+    pub competitive_on_cursor_grab: bool,
+    /// how to treat `WinitSettings` which already exist when the plugin is added.
+    /// default: WinitSettingsPolicy::Overwrite
+    pub winit_settings_policy: WinitSettingsPolicy,
+    /// snap max fps to the nearest divisor or multiple of the monitor refresh rate, to avoid judder
+    /// (for example: 120 on a 144Hz monitor becomes 144). the chosen value is reported by
+    /// [`QuickResponsePacing::effective_max_fps`].
+    /// default: false
+    pub quantize_max_fps: bool,
+    /// ramp the frame limiter down from max fps to base fps when leaving a boosted state (see [`FpsRamp`]).
+    /// default: None (jump straight to base fps)
+    pub fps_ramp: Option<FpsRamp>,
 }
 
 impl Default for QuickResponseParameters {
     fn default() -> Self {
         QuickResponseParameters {
             base_fps: 60.0,
-            max_fps: 120.0,
-            auto_init_default_plugins: true
+            max_fps: MaxFps::Fixed(120.0),
+            auto_init_default_plugins: true,
+            delta_smoothing: DeltaSmoothing::Off,
+            competitive_on_cursor_grab: false,
+            winit_settings_policy: WinitSettingsPolicy::Overwrite,
+            quantize_max_fps: false,
+            fps_ramp: None,
+        }
+    }
+}
+
+impl Default for QuickResponseParametersWithNoBaseFps {
+    fn default() -> Self {
+        QuickResponseParametersWithNoBaseFps {
+            max_fps: MaxFps::Fixed(120.0),
+            auto_init_default_plugins: true,
+            delta_smoothing: DeltaSmoothing::Off,
+            competitive_on_cursor_grab: false,
+            winit_settings_policy: WinitSettingsPolicy::Overwrite,
+            quantize_max_fps: false,
+            fps_ramp: None,
         }
     }
 }
 
+/// evaluates `$body` with `$params` bound to the parameters of the mode, whichever of
+/// [`QuickResponseParameters`] and [`QuickResponseParametersWithNoBaseFps`] they are
+/// (`$none` for `QuickResponseMode::None`), so that a shared field is read or written in one place.
+macro_rules! with_parameters {
+    ($mode:expr, |$params:ident| $body:expr, $none:expr) => {
+        match $mode {
+            QuickResponseMode::FastVsync($params)
+            | QuickResponseMode::Immediate($params)
+            | QuickResponseMode::AutoNoVsync($params)
+            | QuickResponseMode::Vrr($params) => $body,
+            QuickResponseMode::PowerSaving($params) => $body,
+            QuickResponseMode::None(_) => $none,
+        }
+    };
+}
+
+/// a copy of the plugin with a shared parameter set (`QuickResponseMode::None` is kept as is)
+macro_rules! with_parameter {
+    ($plugin:expr, $field:ident, $value:expr) => {{
+        let mut plugin = QuickResponsePlugin {
+            mode: $plugin.mode,
+            _no_framepace_for_test: $plugin._no_framepace_for_test,
+        };
+        let value = $value;
+        with_parameters!(&mut plugin.mode, |params| params.$field = value, ());
+        plugin
+    }};
+}
+
 impl QuickResponsePlugin {
     pub fn new(mode: QuickResponseMode) -> Self {
         QuickResponsePlugin {
@@ -76,34 +212,49 @@ impl QuickResponsePlugin {
         }
     }
 
-    pub fn power_saving(max_fps: f64) -> Self {
+    pub fn power_saving(max_fps: impl Into<MaxFps>) -> Self {
         QuickResponsePlugin::new(QuickResponseMode::PowerSaving(QuickResponseParametersWithNoBaseFps {
-            max_fps,
-            auto_init_default_plugins: true
+            max_fps: max_fps.into(),
+            auto_init_default_plugins: true,
+            ..default()
         }))
     }
 
-    pub fn fast_vsync(base_fps: f64, max_fps: f64) -> Self {
+    pub fn fast_vsync(base_fps: f64, max_fps: impl Into<MaxFps>) -> Self {
         QuickResponsePlugin::new(QuickResponseMode::FastVsync(QuickResponseParameters {
             base_fps,
-            max_fps,
-            auto_init_default_plugins: true
+            max_fps: max_fps.into(),
+            auto_init_default_plugins: true,
+            ..default()
         }))
     }
 
-    pub fn immediate(base_fps: f64, max_fps: f64) -> Self {
+    pub fn immediate(base_fps: f64, max_fps: impl Into<MaxFps>) -> Self {
         QuickResponsePlugin::new(QuickResponseMode::Immediate(QuickResponseParameters {
             base_fps,
-            max_fps,
-            auto_init_default_plugins: true
+            max_fps: max_fps.into(),
+            auto_init_default_plugins: true,
+            ..default()
         }))
     }
 
-    pub fn auto_no_vsync(base_fps: f64, max_fps: f64) -> Self {
+    pub fn auto_no_vsync(base_fps: f64, max_fps: impl Into<MaxFps>) -> Self {
         QuickResponsePlugin::new(QuickResponseMode::AutoNoVsync(QuickResponseParameters {
             base_fps,
-            max_fps,
-            auto_init_default_plugins: true
+            max_fps: max_fps.into(),
+            auto_init_default_plugins: true,
+            ..default()
+        }))
+    }
+
+    /// VRR mode, capped at the refresh rate of the monitor minus `margin_fps`
+    /// (kept correct when the window moves to another monitor)
+    pub fn vrr(base_fps: f64, margin_fps: f64) -> Self {
+        QuickResponsePlugin::new(QuickResponseMode::Vrr(QuickResponseParameters {
+            base_fps,
+            max_fps: MaxFps::BelowMonitor(margin_fps),
+            auto_init_default_plugins: true,
+            ..default()
         }))
     }
 
@@ -119,43 +270,34 @@ impl QuickResponsePlugin {
     }
 
     pub fn with_no_default_plugins(&self) -> Self {
-        match self.mode {
-            QuickResponseMode::None(_) => {
-                QuickResponsePlugin::none(false)
-            }
-            QuickResponseMode::FastVsync(params) => {
-                QuickResponsePlugin::new(
-                    QuickResponseMode::FastVsync(QuickResponseParameters {
-                        auto_init_default_plugins: false,
-                        ..params
-                    })
-                )
-            }
-            QuickResponseMode::Immediate(params) => {
-                QuickResponsePlugin::new(
-                    QuickResponseMode::Immediate(QuickResponseParameters {
-                        auto_init_default_plugins: false,
-                        ..params
-                    })
-                )
-            }
-            QuickResponseMode::AutoNoVsync(params) => {
-                QuickResponsePlugin::new(
-                    QuickResponseMode::AutoNoVsync(QuickResponseParameters {
-                        auto_init_default_plugins: false,
-                        ..params
-                    })
-                )
-            }
-            QuickResponseMode::PowerSaving(params) => {
-                QuickResponsePlugin::new(
-                    QuickResponseMode::PowerSaving(QuickResponseParametersWithNoBaseFps {
-                        auto_init_default_plugins: false,
-                        ..params
-                    })
-                )
-            }
+        if let QuickResponseMode::None(_) = self.mode {
+            return QuickResponsePlugin::none(false);
         }
+        with_parameter!(self, auto_init_default_plugins, false)
+    }
+
+    pub fn with_delta_smoothing(&self, delta_smoothing: DeltaSmoothing) -> Self {
+        with_parameter!(self, delta_smoothing, delta_smoothing)
+    }
+
+    pub fn with_competitive_on_cursor_grab(&self, competitive_on_cursor_grab: bool) -> Self {
+        with_parameter!(self, competitive_on_cursor_grab, competitive_on_cursor_grab)
+    }
+
+    pub fn with_max_fps(&self, max_fps: impl Into<MaxFps>) -> Self {
+        with_parameter!(self, max_fps, max_fps.into())
+    }
+
+    pub fn with_winit_settings_policy(&self, winit_settings_policy: WinitSettingsPolicy) -> Self {
+        with_parameter!(self, winit_settings_policy, winit_settings_policy)
+    }
+
+    pub fn with_quantize_max_fps(&self, quantize_max_fps: bool) -> Self {
+        with_parameter!(self, quantize_max_fps, quantize_max_fps)
+    }
+
+    pub fn with_fps_ramp(&self, fps_ramp: FpsRamp) -> Self {
+        with_parameter!(self, fps_ramp, Some(fps_ramp))
     }
 
     pub fn window_plugin(&self) -> WindowPlugin {
@@ -194,6 +336,15 @@ impl QuickResponsePlugin {
                     ..default()
                 }
             },
+            QuickResponseMode::Vrr(_) => {
+                WindowPlugin {
+                    primary_window: Some(Window {
+                        present_mode: bevy::window::PresentMode::AutoNoVsync,
+                        ..default()
+                    }),
+                    ..default()
+                }
+            },
             QuickResponseMode::PowerSaving(_) => {
                 WindowPlugin {
                     primary_window: Some(Window {
@@ -215,6 +366,26 @@ impl QuickResponsePlugin {
             }
         }
     }
+
+    /// apply the pacing related settings of the mode (present mode) to the primary window of the plugin,
+    /// and keep the other settings (title, resolution, decorations, etc.).
+    /// if the plugin has no primary window, it is returned as is.
+    pub fn apply_to_window_plugin(&self, mut window_plugin: WindowPlugin) -> WindowPlugin {
+        if let Some(window) = window_plugin.primary_window.as_mut() {
+            self.apply_to_window(window);
+        }
+        window_plugin
+    }
+
+    /// apply the pacing related settings of the mode (present mode) to the window,
+    /// and keep the other settings (title, resolution, decorations, frame latency, etc.).
+    /// does nothing for `QuickResponseMode::None`.
+    pub fn apply_to_window(&self, window: &mut Window) {
+        if matches!(self.mode, QuickResponseMode::None(_)) {
+            return;
+        }
+        window.present_mode = present_mode_for_mode(self.mode);
+    }
 }
 
 impl Default for QuickResponsePlugin {
@@ -223,10 +394,9 @@ impl Default for QuickResponsePlugin {
     }
 }
 
-fn setup_fps(max_fps: f64) -> impl Fn(ResMut<FramepaceSettings>) {
-    move |mut framepace_settings: ResMut<FramepaceSettings>| {
-        framepace_settings.limiter = Limiter::from_framerate(max_fps);
-    }
+fn setup_fps(pacing: Res<QuickResponsePacing>, mut framepace_settings: ResMut<FramepaceSettings>) {
+    // read from pacing, because a state profile may have changed the mode before Startup
+    framepace_settings.limiter = limiter_for_mode(pacing.mode, pacing.refresh_rate);
 }
 
 fn is_base_fps_enabled(mode: QuickResponseMode) -> bool {
@@ -234,6 +404,7 @@ fn is_base_fps_enabled(mode: QuickResponseMode) -> bool {
         QuickResponseMode::FastVsync(_) => true,
         QuickResponseMode::Immediate(_) => true,
         QuickResponseMode::AutoNoVsync(_) => true,
+        QuickResponseMode::Vrr(_) => true,
         QuickResponseMode::PowerSaving(_) => false,
         QuickResponseMode::None(_) => false,
     }
@@ -244,11 +415,106 @@ fn is_power_saving_enabled(mode: QuickResponseMode) -> bool {
         QuickResponseMode::FastVsync(_) => false,
         QuickResponseMode::Immediate(_) => false,
         QuickResponseMode::AutoNoVsync(_) => false,
+        QuickResponseMode::Vrr(_) => false,
         QuickResponseMode::PowerSaving(_) => true,
         QuickResponseMode::None(_) => false,
     }
 }
 
+/// base fps of the mode (None for `PowerSaving` and `None`)
+pub(crate) fn base_fps_for_mode(mode: QuickResponseMode) -> Option<f64> {
+    match mode {
+        QuickResponseMode::FastVsync(params)
+        | QuickResponseMode::Immediate(params)
+        | QuickResponseMode::AutoNoVsync(params)
+        | QuickResponseMode::Vrr(params) => Some(params.base_fps),
+        QuickResponseMode::PowerSaving(_) | QuickResponseMode::None(_) => None,
+    }
+}
+
+/// winit settings of the mode (bevy default for `QuickResponseMode::None`)
+pub(crate) fn winit_settings_for_mode(mode: QuickResponseMode) -> WinitSettings {
+    if is_base_fps_enabled(mode) {
+        let base_fps = match mode {
+            QuickResponseMode::FastVsync(params) => params.base_fps,
+            QuickResponseMode::AutoNoVsync(params) => params.base_fps,
+            QuickResponseMode::Vrr(params) => params.base_fps,
+            QuickResponseMode::Immediate(params) => params.base_fps,
+            QuickResponseMode::PowerSaving(_) => unreachable!(),
+            QuickResponseMode::None(_) => unreachable!(),
+        };
+
+        WinitSettings {
+            focused_mode: UpdateMode::reactive_low_power(Duration::from_secs_f64(1.0 / base_fps) ),
+            unfocused_mode: UpdateMode::reactive_low_power(Duration::from_secs_f64(1.0 / base_fps) ),
+        }
+    } else if is_power_saving_enabled(mode) {
+        WinitSettings::desktop_app()
+    } else {
+        WinitSettings::default()
+    }
+}
+
+/// present mode of the primary window of the mode (bevy default for `QuickResponseMode::None`)
+pub(crate) fn present_mode_for_mode(mode: QuickResponseMode) -> PresentMode {
+    QuickResponsePlugin::new(mode).window_plugin().primary_window
+        .map(|window| window.present_mode)
+        .unwrap_or_default()
+}
+
+/// max fps of the mode for the refresh rate (Hz) of the current monitor, quantized if enabled.
+/// None if unknown, unlimited, or `QuickResponseMode::None`.
+pub(crate) fn effective_max_fps_for_mode(mode: QuickResponseMode, refresh_rate: Option<f64>) -> Option<f64> {
+    let (max_fps, quantize) = with_parameters!(mode, |params| (params.max_fps, params.quantize_max_fps), return None);
+
+    let fps = max_fps.resolve(refresh_rate)?;
+    match refresh_rate {
+        Some(refresh_rate) if quantize => Some(max_fps::quantize_to_refresh(fps, refresh_rate)),
+        _ => Some(fps),
+    }
+}
+
+/// frame limiter of the mode (no limit for `QuickResponseMode::None`)
+pub(crate) fn limiter_for_mode(mode: QuickResponseMode, refresh_rate: Option<f64>) -> Limiter {
+    let max_fps = with_parameters!(mode, |params| params.max_fps, return Limiter::Off);
+
+    match max_fps {
+        MaxFps::Auto | MaxFps::Off => max_fps.limiter(refresh_rate),
+        _ => effective_max_fps_for_mode(mode, refresh_rate).map_or(Limiter::Auto, Limiter::from_framerate),
+    }
+}
+
+pub(crate) fn delta_smoothing_settings_for_mode(mode: QuickResponseMode) -> delta_smoothing::DeltaSmoothingSettings {
+    let smoothing = with_parameters!(mode, |params| params.delta_smoothing, DeltaSmoothing::Off);
+
+    // longest frame interval expected while the app is not sleeping
+    let sleep_threshold = match mode {
+        QuickResponseMode::FastVsync(params) => Duration::from_secs_f64(1.5 / params.base_fps),
+        QuickResponseMode::AutoNoVsync(params) => Duration::from_secs_f64(1.5 / params.base_fps),
+        QuickResponseMode::Vrr(params) => Duration::from_secs_f64(1.5 / params.base_fps),
+        QuickResponseMode::Immediate(params) => Duration::from_secs_f64(1.5 / params.base_fps),
+        QuickResponseMode::PowerSaving(params) => Duration::from_secs_f64(1.5 / params.max_fps.resolve(None).unwrap_or(60.0)),
+        QuickResponseMode::None(_) => Duration::from_secs_f64(1.5 / 60.0),
+    };
+
+    delta_smoothing::DeltaSmoothingSettings {
+        smoothing,
+        sleep_threshold,
+    }
+}
+
+pub(crate) fn is_competitive_on_cursor_grab(mode: QuickResponseMode) -> bool {
+    with_parameters!(mode, |params| params.competitive_on_cursor_grab, false)
+}
+
+pub(crate) fn winit_settings_policy_for_mode(mode: QuickResponseMode) -> WinitSettingsPolicy {
+    with_parameters!(mode, |params| params.winit_settings_policy, WinitSettingsPolicy::Overwrite)
+}
+
+pub(crate) fn fps_ramp_for_mode(mode: QuickResponseMode) -> Option<FpsRamp> {
+    with_parameters!(mode, |params| params.fps_ramp, None)
+}
+
 impl Plugin for QuickResponsePlugin {
     fn build(&self, app: &mut App) {
         if self.mode == QuickResponseMode::None(false) {
@@ -260,48 +526,80 @@ impl Plugin for QuickResponsePlugin {
             return;
         }
 
-        if is_base_fps_enabled(self.mode) {
-            let base_fps = match self.mode {
-                QuickResponseMode::FastVsync(params) => params.base_fps,
-                QuickResponseMode::AutoNoVsync(params) => params.base_fps,
-                QuickResponseMode::Immediate(params) => params.base_fps,
-                QuickResponseMode::PowerSaving(_) => unreachable!(),
-                QuickResponseMode::None(_) => unreachable!(),
-            };
-
-            app
-                .insert_resource(WinitSettings {
-                    focused_mode: UpdateMode::reactive_low_power(Duration::from_secs_f64(1.0 / base_fps) ),
-                    unfocused_mode: UpdateMode::reactive_low_power(Duration::from_secs_f64(1.0 / base_fps) ),
-                    ..default()
-                })
-                ;
-        } else if is_power_saving_enabled(self.mode) {
-            app
-                .insert_resource(WinitSettings::desktop_app())
-                ;
-        }
+        let existing = app.world().get_resource::<WinitSettings>().cloned();
+        let winit_settings = winit_policy::resolve_winit_settings(
+            winit_settings_policy_for_mode(self.mode),
+            existing.as_ref(),
+            winit_settings_for_mode(self.mode),
+        );
+        app
+            .insert_resource(winit_policy::WinitSettingsTracker::new(existing.as_ref(), &winit_settings))
+            .insert_resource(winit_settings)
+            ;
 
-        let max_fps = match self.mode {
-            QuickResponseMode::FastVsync(params) => params.max_fps,
-            QuickResponseMode::AutoNoVsync(params) => params.max_fps,
-            QuickResponseMode::Immediate(params) => params.max_fps,
-            QuickResponseMode::PowerSaving(params) => params.max_fps,
-            QuickResponseMode::None(_) => unreachable!(),
-        };
+        let auto_init_default_plugins = with_parameters!(self.mode, |params| params.auto_init_default_plugins, false);
 
-        let auto_init_default_plugins = match self.mode {
-            QuickResponseMode::FastVsync(params) => params.auto_init_default_plugins,
-            QuickResponseMode::AutoNoVsync(params) => params.auto_init_default_plugins,
-            QuickResponseMode::Immediate(params) => params.auto_init_default_plugins,
-            QuickResponseMode::PowerSaving(params) => params.auto_init_default_plugins,
-            QuickResponseMode::None(_) => unreachable!(),
-        };
+        let base_winit_settings = app.world().resource::<WinitSettings>().clone();
 
         app
             .add_plugins(())
+            .insert_resource(QuickResponsePacing::new(self.mode, base_winit_settings, present_mode_for_mode(self.mode)))
+            .insert_resource(delta_smoothing_settings_for_mode(self.mode))
+            .init_resource::<QuickResponseWaker>()
+            .init_resource::<WakeSchedule>()
+            .init_resource::<AssetLoadingBoost>()
+            .init_resource::<ActivityBoostSettings>()
+            .init_resource::<FramePacingStats>()
+            .init_resource::<WakeReason>()
+            .init_resource::<WakeReasonCounts>()
+            .add_message::<WindowResized>()
+            .add_message::<WindowMoved>()
+            .add_message::<GamepadConnectionEvent>()
+            .add_message::<GamepadButtonChangedEvent>()
+            .add_message::<GamepadAxisChangedEvent>()
+            .add_message::<TouchInput>()
+            .add_message::<QuickResponseChanged>()
+            .add_message::<KeyboardInput>()
+            .add_message::<MouseButtonInput>()
+            .add_message::<MouseWheel>()
+            .add_message::<CursorMoved>()
+            .add_message::<AppExit>()
+            .add_message::<WindowEvent>()
+            .add_message::<RequestRedraw>()
+            .add_message::<MouseMotion>()
+            .configure_sets(Last, (
+                QuickResponseSystems::Detect,
+                QuickResponseSystems::Apply,
+            ).chain())
+            .add_systems(Last, ((
+                keep_awake::detect_keep_awake,
+                waker::detect_waker_boost,
+                wake_schedule::detect_wake_schedule,
+                asset_loading::detect_asset_loading,
+                activity::detect_window_activity,
+                activity::detect_gamepad_activity,
+                activity::detect_touch_activity,
+                cursor_grab::detect_cursor_grab,
+                max_fps::detect_monitor_refresh_rate,
+                idle_tiers::detect_idle_tier,
+            ), fps_ramp::detect_fps_ramp).chain().in_set(QuickResponseSystems::Detect))
+            .add_systems(Last, (
+                pacing::apply_pacing,
+                cursor_grab::apply_cursor_grab_present_mode,
+                max_fps::apply_limiter,
+                changes::notify_changes,
+            ).chain().in_set(QuickResponseSystems::Apply))
+            .add_systems(First, (trace::trace_frame_start, delta_smoothing::smooth_delta).chain().before(TimeSystems))
+            .add_systems(First, (
+                wake_reason::detect_wake_reason,
+                (stats::record_frame_stats, recorder::record_frame_start),
+            ).chain().after(TimeSystems))
+            .add_systems(Last, (recorder::record_frame_end, trace::trace_frame_end).after(QuickResponseSystems::Apply))
+            .configure_sets(Update, LowFrequency.run_if(conditions::low_frequency_due))
             ;
 
+        wake_reason::register_wake_diagnostics(app);
+
         if auto_init_default_plugins {
             app.add_plugins(DefaultPlugins.set(
                 self.window_plugin()
@@ -312,9 +610,13 @@ impl Plugin for QuickResponsePlugin {
             if !app.is_plugin_added::<FramepacePlugin>() {
                 app.add_plugins(FramepacePlugin);
             }
-            app.add_systems(Startup, setup_fps(max_fps));
+            app.add_systems(Startup, setup_fps);
         }
     }
+
+    fn finish(&self, app: &mut App) {
+        waker::connect_waker(app);
+    }
 }
 
 #[cfg(test)] #[macro_use]
@@ -352,7 +654,7 @@ mod tests {
         let pl = QuickResponsePlugin::power_saving(60.0);
 
         assert_matches!(pl.mode, QuickResponseMode::PowerSaving(
-            QuickResponseParametersWithNoBaseFps { max_fps: x, auto_init_default_plugins: true })
+            QuickResponseParametersWithNoBaseFps { max_fps: MaxFps::Fixed(x), auto_init_default_plugins: true, .. })
             if float_eq(x, 60.0)
         );
 
@@ -384,7 +686,7 @@ mod tests {
         let pl = QuickResponsePlugin::default();
 
         assert_matches!(pl.mode, QuickResponseMode::FastVsync(
-            QuickResponseParameters { base_fps: x, max_fps: y, auto_init_default_plugins: true })
+            QuickResponseParameters { base_fps: x, max_fps: MaxFps::Fixed(y), auto_init_default_plugins: true, .. })
             if float_eq(x, 60.0) && float_eq(y, 120.0)
         );
 
@@ -416,7 +718,7 @@ mod tests {
         let pl = QuickResponsePlugin::new(QuickResponseMode::FastVsync(QuickResponseParameters::default()));
 
         assert_matches!(pl.mode, QuickResponseMode::FastVsync(
-            QuickResponseParameters { base_fps: x, max_fps: y, auto_init_default_plugins: true })
+            QuickResponseParameters { base_fps: x, max_fps: MaxFps::Fixed(y), auto_init_default_plugins: true, .. })
             if float_eq(x, 60.0) && float_eq(y, 120.0)
         );
 
@@ -448,7 +750,7 @@ mod tests {
         let pl = QuickResponsePlugin::new(QuickResponseMode::Immediate(QuickResponseParameters::default()));
 
         assert_matches!(pl.mode, QuickResponseMode::Immediate(
-            QuickResponseParameters { base_fps: x, max_fps: y, auto_init_default_plugins: true })
+            QuickResponseParameters { base_fps: x, max_fps: MaxFps::Fixed(y), auto_init_default_plugins: true, .. })
             if float_eq(x, 60.0) && float_eq(y, 120.0)
         );
 
@@ -474,7 +776,7 @@ mod tests {
         let pl = QuickResponsePlugin::new(QuickResponseMode::AutoNoVsync(QuickResponseParameters::default()));
 
         assert_matches!(pl.mode, QuickResponseMode::AutoNoVsync(
-            QuickResponseParameters { base_fps: x, max_fps: y, auto_init_default_plugins: true })
+            QuickResponseParameters { base_fps: x, max_fps: MaxFps::Fixed(y), auto_init_default_plugins: true, .. })
             if float_eq(x, 60.0) && float_eq(y, 120.0)
         );
 
@@ -494,4 +796,53 @@ mod tests {
             .add_plugins(pl)
             .update()
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_plugin_vrr() {
+        let pl = QuickResponsePlugin::vrr(60.0, 3.0);
+
+        assert_matches!(pl.mode, QuickResponseMode::Vrr(
+            QuickResponseParameters { base_fps: x, max_fps: MaxFps::BelowMonitor(y), auto_init_default_plugins: true, .. })
+            if float_eq(x, 60.0) && float_eq(y, 3.0)
+        );
+
+        let pl = pl
+            .with_no_default_plugins()
+            .with_no_framepace_for_test();
+
+        let window_pl = pl.window_plugin();
+
+        assert_matches!(window_pl.primary_window, Some(Window {
+            present_mode: bevy::window::PresentMode::AutoNoVsync, .. })
+        );
+
+        App::new()
+            .add_plugins(MinimalPlugins)
+            .add_plugins(window_pl)
+            .add_plugins(pl)
+            .update()
+    }
+
+    #[test]
+    fn test_apply_to_window_plugin() {
+        let pl = QuickResponsePlugin::immediate(60.0, 240.0);
+
+        let window_pl = pl.apply_to_window_plugin(WindowPlugin {
+            primary_window: Some(Window {
+                title: "Test".to_string(),
+                decorations: false,
+                ..default()
+            }),
+            ..default()
+        });
+
+        assert_matches!(window_pl.primary_window, Some(Window {
+            present_mode: bevy::window::PresentMode::Immediate, decorations: false, ref title, .. })
+            if title == "Test"
+        );
+
+        let mut window = Window { present_mode: PresentMode::Fifo, ..default() };
+        QuickResponsePlugin::none(false).apply_to_window(&mut window);
+        assert_eq!(window.present_mode, PresentMode::Fifo);
+    }
+}
@@ -0,0 +1,226 @@
+use bevy::{prelude::*, window::{Monitor, PrimaryMonitor, PrimaryWindow, WindowPosition}};
+use bevy_framepace::{FramepaceSettings, Limiter};
+
+use crate::{QuickResponsePacing, limiter_for_mode};
+
+/// Max fps, for example: when mouse moves over window (the frame limiter while boosted).
+///
+/// a fixed fps converts from `f64`, so `max_fps: 120.0.into()` works.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MaxFps {
+    /// a fixed fps
+    Fixed(f64),
+    /// the refresh rate of the monitor the primary window is on.
+    /// updated when the window moves to a different monitor.
+    MatchMonitor,
+    /// the refresh rate of the monitor the primary window is on, multiplied by the value
+    MonitorMultiple(f64),
+    /// the refresh rate of the monitor the primary window is on, minus the margin (for VRR displays).
+    /// while the refresh rate is unknown, [`MaxFps::FALLBACK_REFRESH_RATE`] minus the margin is used,
+    /// because `Limiter::Auto` would cap at the refresh rate (and leave the VRR range).
+    BelowMonitor(f64),
+    /// bevy_framepace detects the refresh rate of the window (`Limiter::Auto`)
+    Auto,
+    /// no frame limit (`Limiter::Off`)
+    Off,
+}
+
+impl Default for MaxFps {
+    fn default() -> Self {
+        MaxFps::Fixed(120.0)
+    }
+}
+
+impl From<f64> for MaxFps {
+    fn from(fps: f64) -> Self {
+        MaxFps::Fixed(fps)
+    }
+}
+
+impl MaxFps {
+    /// refresh rate (Hz) assumed by `MaxFps::BelowMonitor` until the monitor is known (conservative for VRR displays)
+    pub const FALLBACK_REFRESH_RATE: f64 = 60.0;
+
+    /// the fps for the refresh rate (Hz) of the current monitor.
+    /// None if it depends on the monitor and the refresh rate is unknown (except for `MaxFps::BelowMonitor`), or for `MaxFps::Off`.
+    pub fn resolve(&self, refresh_rate: Option<f64>) -> Option<f64> {
+        match *self {
+            MaxFps::Fixed(fps) => Some(fps),
+            MaxFps::MatchMonitor => refresh_rate,
+            MaxFps::MonitorMultiple(multiple) => refresh_rate.map(|refresh_rate| refresh_rate * multiple),
+            MaxFps::BelowMonitor(margin) => Some((refresh_rate.unwrap_or(Self::FALLBACK_REFRESH_RATE) - margin).max(1.0)),
+            MaxFps::Auto => refresh_rate,
+            MaxFps::Off => None,
+        }
+    }
+
+    /// frame limiter for the refresh rate (Hz) of the current monitor.
+    /// if the refresh rate is needed but unknown, bevy_framepace detects it (`Limiter::Auto`),
+    /// except for `MaxFps::BelowMonitor` (see [`MaxFps::FALLBACK_REFRESH_RATE`]).
+    pub fn limiter(&self, refresh_rate: Option<f64>) -> Limiter {
+        match self {
+            MaxFps::Auto => Limiter::Auto,
+            MaxFps::Off => Limiter::Off,
+            _ => self.resolve(refresh_rate).map_or(Limiter::Auto, Limiter::from_framerate),
+        }
+    }
+}
+
+/// the divisor (refresh / n) or multiple (refresh * n) of the refresh rate nearest to the fps
+pub(crate) fn quantize_to_refresh(fps: f64, refresh_rate: f64) -> f64 {
+    if fps >= refresh_rate {
+        refresh_rate * (fps / refresh_rate).round()
+    } else {
+        let n = (refresh_rate / fps).floor().max(1.0);
+        // compare the two nearest divisors around the fps
+        let (higher, lower) = (refresh_rate / n, refresh_rate / (n + 1.0));
+        if higher - fps <= fps - lower { higher } else { lower }
+    }
+}
+
+fn monitor_contains(monitor: &Monitor, point: IVec2) -> bool {
+    let min = monitor.physical_position;
+    let max = min + monitor.physical_size().as_ivec2();
+    point.cmpge(min).all() && point.cmplt(max).all()
+}
+
+pub(crate) fn detect_monitor_refresh_rate(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    monitors: Query<(&Monitor, Has<PrimaryMonitor>)>,
+    mut pacing: ResMut<QuickResponsePacing>,
+) {
+    let center = windows.single().ok().and_then(|window| match window.position {
+        WindowPosition::At(position) => Some(position + window.physical_size().as_ivec2() / 2),
+        _ => None,
+    });
+
+    // the monitor containing the center of the window, or the primary monitor
+    let monitor = center
+        .and_then(|center| monitors.iter().find(|(monitor, _)| monitor_contains(monitor, center)))
+        .or_else(|| monitors.iter().find(|(_, primary)| *primary));
+
+    let refresh_rate = monitor
+        .and_then(|(monitor, _)| monitor.refresh_rate_millihertz)
+        .map(|millihertz| millihertz as f64 / 1000.0);
+
+    if pacing.refresh_rate != refresh_rate {
+        pacing.refresh_rate = refresh_rate;
+    }
+}
+
+fn same_limiter(a: &Limiter, b: &Limiter) -> bool {
+    match (a, b) {
+        (Limiter::Auto, Limiter::Auto) => true,
+        (Limiter::Off, Limiter::Off) => true,
+        (Limiter::Manual(a), Limiter::Manual(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// update the frame limiter only when the resolved limiter changes (for example: the window moved to another monitor, or ramping down after a boost),
+/// so that limiter changes made by the user in between are kept.
+pub(crate) fn apply_limiter(
+    pacing: Res<QuickResponsePacing>,
+    framepace_settings: Option<ResMut<FramepaceSettings>>,
+    mut last: Local<Option<Limiter>>,
+) {
+    let Some(mut framepace_settings) = framepace_settings else {
+        return;
+    };
+
+    let limiter = match pacing.ramp_fps {
+        Some(fps) => Limiter::from_framerate(fps),
+        None => limiter_for_mode(pacing.mode, pacing.refresh_rate),
+    };
+    if last.as_ref().is_some_and(|last| same_limiter(last, &limiter)) {
+        return;
+    }
+
+    *last = Some(limiter.clone());
+    if !same_limiter(&framepace_settings.limiter, &limiter) {
+        framepace_settings.limiter = limiter;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::QuickResponsePlugin;
+
+    use super::*;
+
+    fn monitor(x: i32, refresh_rate_millihertz: u32) -> Monitor {
+        Monitor {
+            name: None,
+            physical_height: 1080,
+            physical_width: 1920,
+            physical_position: IVec2::new(x, 0),
+            refresh_rate_millihertz: Some(refresh_rate_millihertz),
+            scale_factor: 1.0,
+            video_modes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_match_monitor() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .init_resource::<FramepaceSettings>()
+            .add_plugins(QuickResponsePlugin::fast_vsync(60.0, MaxFps::MatchMonitor)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+
+        app.world_mut().spawn((monitor(0, 60_000), PrimaryMonitor));
+        app.world_mut().spawn(monitor(1920, 144_000));
+        let window = app.world_mut().spawn((
+            Window { position: WindowPosition::At(IVec2::new(100, 100)), ..default() },
+            PrimaryWindow,
+        )).id();
+
+        app.update();
+        assert_eq!(app.world().resource::<QuickResponsePacing>().refresh_rate, Some(60.0));
+        assert_matches!(app.world().resource::<FramepaceSettings>().limiter,
+            Limiter::Manual(d) if d == Duration::from_secs_f64(1.0 / 60.0));
+
+        // moved to the second monitor
+        app.world_mut().get_mut::<Window>(window).unwrap().position = WindowPosition::At(IVec2::new(2000, 100));
+        app.update();
+        assert_eq!(app.world().resource::<QuickResponsePacing>().refresh_rate, Some(144.0));
+        assert_matches!(app.world().resource::<FramepaceSettings>().limiter,
+            Limiter::Manual(d) if d == Duration::from_secs_f64(1.0 / 144.0));
+    }
+
+    #[test]
+    fn test_quantize_to_refresh() {
+        assert_eq!(quantize_to_refresh(120.0, 144.0), 144.0);
+        assert_eq!(quantize_to_refresh(60.0, 144.0), 72.0);
+        assert_eq!(quantize_to_refresh(40.0, 144.0), 36.0);
+        assert_eq!(quantize_to_refresh(300.0, 144.0), 288.0);
+
+        let pl = QuickResponsePlugin::fast_vsync(60.0, 120.0).with_quantize_max_fps(true);
+        assert_eq!(crate::effective_max_fps_for_mode(pl.mode, Some(144.0)), Some(144.0));
+        assert_eq!(crate::effective_max_fps_for_mode(pl.mode, None), Some(120.0));
+    }
+
+    #[test]
+    fn test_limiter() {
+        assert_matches!(MaxFps::Auto.limiter(Some(144.0)), Limiter::Auto);
+        assert_matches!(MaxFps::Off.limiter(Some(144.0)), Limiter::Off);
+        assert_matches!(MaxFps::MatchMonitor.limiter(None), Limiter::Auto);
+        assert_matches!(MaxFps::MonitorMultiple(0.5).limiter(Some(144.0)),
+            Limiter::Manual(d) if d == Duration::from_secs_f64(1.0 / 72.0));
+
+        assert_matches!(MaxFps::BelowMonitor(3.0).limiter(Some(144.0)),
+            Limiter::Manual(d) if d == Duration::from_secs_f64(1.0 / 141.0));
+        // not capped at the refresh rate while the monitor is unknown
+        assert_matches!(MaxFps::BelowMonitor(3.0).limiter(None),
+            Limiter::Manual(d) if d == Duration::from_secs_f64(1.0 / 57.0));
+
+        let pl = QuickResponsePlugin::immediate(60.0, MaxFps::Off);
+        assert_matches!(crate::limiter_for_mode(pl.mode, Some(60.0)), Limiter::Off);
+        let pl = pl.with_max_fps(MaxFps::Auto);
+        assert_matches!(crate::limiter_for_mode(pl.mode, Some(60.0)), Limiter::Auto);
+    }
+}
@@ -0,0 +1,277 @@
+use std::time::Duration;
+
+use bevy::{platform::time::Instant, prelude::*, window::PresentMode, winit::{UpdateMode, WinitSettings}};
+
+use crate::{IdleTier, QuickResponseEffectiveSettings, QuickResponseMode, effective_max_fps_for_mode, winit_settings_for_mode, winit_settings_policy_for_mode};
+use crate::winit_policy::{WinitSettingsTracker, resolve_winit_settings};
+
+/// System sets used by [`QuickResponsePlugin`](crate::QuickResponsePlugin) to update pacing at runtime.
+/// both sets run in the [`Last`] schedule, so the result is used for the next wait of the event loop.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum QuickResponseSystems {
+    /// collect activity (for example: [`KeepAwake`](crate::KeepAwake) entities, boost requests, scheduled wake-ups, input activity) into [`QuickResponsePacing`]
+    Detect,
+    /// write the effective settings into [`WinitSettings`] (and the present mode of the primary window)
+    Apply,
+}
+
+/// Runtime pacing state of [`QuickResponsePlugin`](crate::QuickResponsePlugin).
+/// (inserted only when the plugin changes pacing, i.e. not in `QuickResponseMode::None`)
+#[derive(Resource, Debug, Clone)]
+pub struct QuickResponsePacing {
+    /// the current mode (changed at runtime by [`QuickResponseStatePlugin`](crate::QuickResponseStatePlugin))
+    pub mode: QuickResponseMode,
+    /// winit settings configured by the plugin.
+    /// restored when nothing keeps the app awake.
+    pub base_winit_settings: WinitSettings,
+    /// present mode of the primary window configured by the plugin.
+    /// restored when the competitive mode ends.
+    pub base_present_mode: PresentMode,
+    /// true while at least one [`KeepAwake`](crate::KeepAwake) entity exists
+    pub keep_awake: bool,
+    /// true while the cursor of the primary window is locked
+    /// (only tracked when `competitive_on_cursor_grab` is enabled in the current mode)
+    pub cursor_grabbed: bool,
+    /// true while assets tracked by [`AssetLoadingBoost`](crate::AssetLoadingBoost) are loading
+    pub loading_assets: bool,
+    /// the app is boosted (continuous at max fps) until this instant
+    pub boost_until: Option<Instant>,
+    /// time from the start of the current update until the next scheduled wake-up (see [`WakeSchedule`](crate::WakeSchedule)).
+    /// the wait of reactive update modes is shortened to this.
+    pub wake_in: Option<Duration>,
+    /// maximum wait while external input has to be polled (for example: gamepads, which do not wake winit).
+    /// the wait of the focused reactive update mode is shortened to this (unfocused windows are not polled).
+    pub poll_interval: Option<Duration>,
+    /// true if the current frame followed a sleep (a frame interval longer than the base fps interval)
+    pub after_sleep: bool,
+    /// refresh rate (Hz) of the monitor the primary window is on (None if unknown)
+    pub refresh_rate: Option<f64>,
+    /// the idle tier entered after inactivity (see [`IdleTier`]).
+    /// the wait of reactive update modes is lengthened to the tier.
+    pub idle_tier: Option<IdleTier>,
+    /// fps of the frame limiter while ramping down after a boost (see [`FpsRamp`](crate::FpsRamp)).
+    /// the app is still regarded as boosted (continuous) while ramping.
+    pub ramp_fps: Option<f64>,
+}
+
+impl QuickResponsePacing {
+    pub fn new(mode: QuickResponseMode, base_winit_settings: WinitSettings, base_present_mode: PresentMode) -> Self {
+        QuickResponsePacing {
+            mode,
+            base_winit_settings,
+            base_present_mode,
+            keep_awake: false,
+            cursor_grabbed: false,
+            loading_assets: false,
+            boost_until: None,
+            wake_in: None,
+            poll_interval: None,
+            after_sleep: false,
+            refresh_rate: None,
+            idle_tier: None,
+            ramp_fps: None,
+        }
+    }
+
+    /// keep the app continuous at max fps for (at least) the given duration from now
+    pub fn request_boost(&mut self, duration: Duration) {
+        self.request_boost_until(Instant::now() + duration);
+    }
+
+    /// keep the app continuous at max fps until (at least) the given instant.
+    /// a running boost is never shortened.
+    pub fn request_boost_until(&mut self, until: Instant) {
+        if self.boost_until.is_none_or(|current| current < until) {
+            self.boost_until = Some(until);
+        }
+    }
+
+    /// true while a requested boost is running
+    pub fn is_boosted(&self) -> bool {
+        self.boost_until.is_some_and(|until| Instant::now() < until)
+    }
+
+    /// true if the focused update mode is currently forced to be continuous (limited by max fps),
+    /// including the ramp down after a boost (see [`FpsRamp`](crate::FpsRamp))
+    pub fn is_continuous(&self) -> bool {
+        self.is_awake_requested() || self.ramp_fps.is_some()
+    }
+
+    /// true if anything but the ramp keeps the app continuous
+    pub(crate) fn is_awake_requested(&self) -> bool {
+        self.keep_awake || self.cursor_grabbed || self.loading_assets || self.is_boosted()
+    }
+
+    /// the update mode which should be used when the window is focused
+    pub fn focused_mode(&self) -> UpdateMode {
+        if self.is_continuous() {
+            UpdateMode::Continuous
+        } else {
+            self.shorten_wait(self.lengthen_wait(self.base_winit_settings.focused_mode), true)
+        }
+    }
+
+    /// the update mode which should be used when the window is not focused
+    pub fn unfocused_mode(&self) -> UpdateMode {
+        self.shorten_wait(self.lengthen_wait(self.base_winit_settings.unfocused_mode), false)
+    }
+
+    /// present mode which should be used for the primary window
+    pub fn present_mode(&self) -> PresentMode {
+        if self.cursor_grabbed {
+            PresentMode::Immediate
+        } else {
+            self.base_present_mode
+        }
+    }
+
+    /// true while nothing keeps the app awake, and no wake-up is scheduled or polled
+    pub fn is_idle(&self) -> bool {
+        !self.is_continuous() && self.wake_in.is_none() && self.poll_interval.is_none()
+    }
+
+    /// max fps for the current monitor (quantized if `quantize_max_fps` is enabled).
+    /// None if unknown (bevy_framepace detects it), or unlimited.
+    pub fn effective_max_fps(&self) -> Option<f64> {
+        effective_max_fps_for_mode(self.mode, self.refresh_rate)
+    }
+
+    pub fn effective_settings(&self) -> QuickResponseEffectiveSettings {
+        QuickResponseEffectiveSettings {
+            mode: self.mode,
+            focused_mode: self.focused_mode(),
+            unfocused_mode: self.unfocused_mode(),
+            present_mode: self.present_mode(),
+            max_fps: self.effective_max_fps(),
+            boosted: self.is_continuous(),
+            idle: self.is_idle(),
+        }
+    }
+
+    fn lengthen_wait(&self, mode: UpdateMode) -> UpdateMode {
+        match (mode, self.idle_tier) {
+            (UpdateMode::Reactive { wait, react_to_device_events, react_to_user_events, react_to_window_events }, Some(tier)) => {
+                UpdateMode::Reactive {
+                    wait: wait.max(tier.wait()),
+                    react_to_device_events,
+                    react_to_user_events,
+                    react_to_window_events,
+                }
+            }
+            _ => mode,
+        }
+    }
+
+    fn shorten_wait(&self, mode: UpdateMode, poll: bool) -> UpdateMode {
+        let poll_interval = self.poll_interval.filter(|_| poll);
+        let max_wait = match (self.wake_in, poll_interval) {
+            (Some(wake_in), Some(poll_interval)) => Some(wake_in.min(poll_interval)),
+            (wake_in, poll_interval) => wake_in.or(poll_interval),
+        };
+
+        match (mode, max_wait) {
+            (UpdateMode::Reactive { wait, react_to_device_events, react_to_user_events, react_to_window_events }, Some(max_wait)) => {
+                UpdateMode::Reactive {
+                    wait: wait.min(max_wait),
+                    react_to_device_events,
+                    react_to_user_events,
+                    react_to_window_events,
+                }
+            }
+            _ => mode,
+        }
+    }
+}
+
+/// writes the update modes only when pacing changes them.
+/// if the user or another plugin changed an update mode of `WinitSettings` since the last write, the change is adopted
+/// into `base_winit_settings` instead of being undone (like the max delta in delta smoothing).
+/// only the changed update mode is adopted, so temporary values of the plugin (boosts, shortened or lengthened waits)
+/// never become the base.
+/// before the first update (for example: inserted by plugins added later), it is resolved by the policy instead.
+pub(crate) fn apply_pacing(
+    mut pacing: ResMut<QuickResponsePacing>,
+    mut winit_settings: ResMut<WinitSettings>,
+    mut tracker: ResMut<WinitSettingsTracker>,
+) {
+    let (applied_focused, applied_unfocused) = tracker.applied;
+    let focused_changed = winit_settings.focused_mode != applied_focused;
+    let unfocused_changed = winit_settings.unfocused_mode != applied_unfocused;
+    if focused_changed || unfocused_changed {
+        if tracker.started {
+            let mut existing = tracker.existing.clone().unwrap_or_else(|| pacing.base_winit_settings.clone());
+            if focused_changed {
+                pacing.base_winit_settings.focused_mode = winit_settings.focused_mode;
+                existing.focused_mode = winit_settings.focused_mode;
+            }
+            if unfocused_changed {
+                pacing.base_winit_settings.unfocused_mode = winit_settings.unfocused_mode;
+                existing.unfocused_mode = winit_settings.unfocused_mode;
+            }
+            tracker.existing = Some(existing);
+        } else {
+            let existing = winit_settings.clone();
+            pacing.base_winit_settings = resolve_winit_settings(winit_settings_policy_for_mode(pacing.mode), Some(&existing), winit_settings_for_mode(pacing.mode));
+            tracker.existing = Some(existing);
+        }
+    }
+    if !tracker.started {
+        tracker.started = true;
+    }
+
+    let focused_mode = pacing.focused_mode();
+    let unfocused_mode = pacing.unfocused_mode();
+
+    // avoid triggering change detection every frame
+    if winit_settings.focused_mode != focused_mode {
+        winit_settings.focused_mode = focused_mode;
+    }
+    if winit_settings.unfocused_mode != unfocused_mode {
+        winit_settings.unfocused_mode = unfocused_mode;
+    }
+    if tracker.applied != (focused_mode, unfocused_mode) {
+        tracker.applied = (focused_mode, unfocused_mode);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{KeepAwake, QuickResponsePlugin};
+
+    use super::*;
+
+    #[test]
+    fn test_apply_pacing_adopts_outside_changes() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(QuickResponsePlugin::fast_vsync(60.0, 120.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+        app.update();
+
+        // changed at runtime by the user
+        *app.world_mut().resource_mut::<WinitSettings>() = WinitSettings::desktop_app();
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+        assert_eq!(app.world().resource::<QuickResponsePacing>().base_winit_settings.unfocused_mode, WinitSettings::desktop_app().unfocused_mode);
+
+        // boosts still switch to continuous, and return to the adopted settings
+        let keep_awake = app.world_mut().spawn(KeepAwake).id();
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::Continuous);
+        app.world_mut().despawn(keep_awake);
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+
+        // only the changed mode is adopted: the boost (continuous) does not become the base
+        let keep_awake = app.world_mut().spawn(KeepAwake).id();
+        app.update();
+        app.world_mut().resource_mut::<WinitSettings>().unfocused_mode = UpdateMode::reactive_low_power(Duration::from_secs(1));
+        app.world_mut().despawn(keep_awake);
+        app.update();
+        let winit_settings = app.world().resource::<WinitSettings>();
+        assert_eq!(winit_settings.focused_mode, WinitSettings::desktop_app().focused_mode);
+        assert_eq!(winit_settings.unfocused_mode, UpdateMode::reactive_low_power(Duration::from_secs(1)));
+    }
+}
@@ -0,0 +1,112 @@
+use bevy::{app::PluginGroupBuilder, prelude::*, window::WindowPlugin};
+
+use crate::QuickResponsePlugin;
+
+type PluginsCustomizer = Box<dyn FnOnce(PluginGroupBuilder) -> PluginGroupBuilder>;
+
+/// `DefaultPlugins` + [`QuickResponsePlugin`], with customization of the window and the other plugins.
+/// the present mode of the mode is kept, even if the window is customized.
+///
+/// ```ignore
+/// App::new()
+///     .add_plugins(QuickResponsePlugins::new(QuickResponsePlugin::default())
+///         .window(|window| window.title = "My App".to_string())
+///         .plugins(|plugins| plugins.set(ImagePlugin::default_nearest())))
+///     .run();
+/// ```
+pub struct QuickResponsePlugins {
+    plugin: QuickResponsePlugin,
+    window_plugin: WindowPlugin,
+    customizers: Vec<PluginsCustomizer>,
+}
+
+impl QuickResponsePlugins {
+    /// `auto_init_default_plugins` of the plugin is ignored, the default plugins are always added.
+    pub fn new(plugin: QuickResponsePlugin) -> Self {
+        let window_plugin = plugin.window_plugin();
+        QuickResponsePlugins {
+            plugin: plugin.with_no_default_plugins(),
+            window_plugin,
+            customizers: Vec::new(),
+        }
+    }
+
+    /// customize the primary window (title, resolution, etc.)
+    pub fn window(mut self, customize: impl FnOnce(&mut Window)) -> Self {
+        if let Some(window) = self.window_plugin.primary_window.as_mut() {
+            customize(window);
+        }
+        self
+    }
+
+    /// customize the window plugin (exit condition, etc.)
+    pub fn window_plugin(mut self, customize: impl FnOnce(&mut WindowPlugin)) -> Self {
+        customize(&mut self.window_plugin);
+        self
+    }
+
+    /// customize the default plugins (set, disable, or add plugins).
+    /// `WindowPlugin` set here is replaced, use [`window`](Self::window) or [`window_plugin`](Self::window_plugin) instead.
+    pub fn plugins(mut self, customize: impl FnOnce(PluginGroupBuilder) -> PluginGroupBuilder + 'static) -> Self {
+        self.customizers.push(Box::new(customize));
+        self
+    }
+}
+
+impl Default for QuickResponsePlugins {
+    fn default() -> Self {
+        QuickResponsePlugins::new(QuickResponsePlugin::default())
+    }
+}
+
+impl QuickResponsePlugins {
+    /// restore the present mode of the mode, after the window is customized
+    fn keep_present_mode(&mut self) {
+        if let Some(window) = self.window_plugin.primary_window.as_mut() {
+            self.plugin.apply_to_window(window);
+        }
+    }
+}
+
+impl PluginGroup for QuickResponsePlugins {
+    fn build(mut self) -> PluginGroupBuilder {
+        self.keep_present_mode();
+
+        let mut group = DefaultPlugins.build();
+        for customize in self.customizers {
+            group = customize(group);
+        }
+
+        group
+            .set(self.window_plugin)
+            .add(self.plugin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::window::PresentMode;
+
+    use super::*;
+
+    #[test]
+    fn test_plugin_group() {
+        let mut plugins = QuickResponsePlugins::new(QuickResponsePlugin::immediate(60.0, 240.0))
+            .window(|window| {
+                window.title = "Test".to_string();
+                window.present_mode = PresentMode::Fifo;
+            });
+
+        plugins.keep_present_mode();
+        let window = plugins.window_plugin.primary_window.as_ref().unwrap();
+        assert_eq!(window.title, "Test");
+        assert_eq!(window.present_mode, PresentMode::Immediate);
+
+        let group = plugins
+            .plugins(|plugins| plugins.disable::<bevy::asset::AssetPlugin>())
+            .build();
+        assert!(group.contains::<QuickResponsePlugin>());
+        assert!(group.enabled::<WindowPlugin>());
+        assert!(!group.enabled::<bevy::asset::AssetPlugin>());
+    }
+}
@@ -0,0 +1,197 @@
+use std::{fmt::Write as _, io, path::PathBuf, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{QuickResponseMode, QuickResponsePacing, WakeReason, stats::target_interval};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameRecordFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+/// One frame recorded by [`FrameRecorder`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameRecord {
+    /// time since startup, at the start of the frame
+    pub timestamp: Duration,
+    /// interval from the previous frame
+    pub interval: Duration,
+    /// target interval (None while not boosted: the app waits for input)
+    pub target: Option<Duration>,
+    pub mode: &'static str,
+    /// true while the app is boosted (continuous at max fps)
+    pub boosted: bool,
+    pub wake_reason: WakeReason,
+}
+
+/// Opt-in per-frame recorder, for benchmarking modes against each other (records are kept in memory until cleared).
+/// insert it as a resource to start recording; the records are written to `path` on exit
+/// (if `write_on_exit`), or on demand by [`save`](Self::save).
+/// input latency is not recorded: bevy input messages carry no timestamps to measure it from.
+///
+/// ```ignore
+/// app.insert_resource(FrameRecorder::new("frames.csv", FrameRecordFormat::Csv));
+/// ```
+#[derive(Resource, Debug, Clone)]
+pub struct FrameRecorder {
+    pub path: PathBuf,
+    pub format: FrameRecordFormat,
+    /// default: true
+    pub write_on_exit: bool,
+    records: Vec<FrameRecord>,
+    current: Option<FrameRecord>,
+}
+
+pub(crate) fn mode_name(mode: QuickResponseMode) -> &'static str {
+    match mode {
+        QuickResponseMode::FastVsync(_) => "FastVsync",
+        QuickResponseMode::Immediate(_) => "Immediate",
+        QuickResponseMode::AutoNoVsync(_) => "AutoNoVsync",
+        QuickResponseMode::Vrr(_) => "Vrr",
+        QuickResponseMode::PowerSaving(_) => "PowerSaving",
+        QuickResponseMode::None(_) => "None",
+    }
+}
+
+fn secs(duration: Option<Duration>) -> String {
+    duration.map_or(String::new(), |duration| format!("{:.6}", duration.as_secs_f64()))
+}
+
+impl FrameRecorder {
+    pub fn new(path: impl Into<PathBuf>, format: FrameRecordFormat) -> Self {
+        FrameRecorder {
+            path: path.into(),
+            format,
+            write_on_exit: true,
+            records: Vec::new(),
+            current: None,
+        }
+    }
+
+    pub fn records(&self) -> &[FrameRecord] {
+        &self.records
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// the records in the format (durations in seconds)
+    pub fn contents(&self) -> String {
+        let mut out = String::new();
+        match self.format {
+            FrameRecordFormat::Csv => {
+                out.push_str("timestamp,interval,target,mode,boosted,wake_reason\n");
+                for record in &self.records {
+                    let _ = writeln!(out, "{},{},{},{},{},{}",
+                        secs(Some(record.timestamp)), secs(Some(record.interval)), secs(record.target),
+                        record.mode, record.boosted, record.wake_reason.name());
+                }
+            }
+            FrameRecordFormat::Json => {
+                let null_or = |duration: Option<Duration>| duration.map_or("null".to_string(), |_| secs(duration));
+                out.push_str("[\n");
+                for (i, record) in self.records.iter().enumerate() {
+                    let _ = write!(out, "  {{\"timestamp\":{},\"interval\":{},\"target\":{},\"mode\":\"{}\",\"boosted\":{},\"wake_reason\":\"{}\"}}",
+                        secs(Some(record.timestamp)), secs(Some(record.interval)), null_or(record.target),
+                        record.mode, record.boosted, record.wake_reason.name());
+                    out.push_str(if i + 1 < self.records.len() { ",\n" } else { "\n" });
+                }
+                out.push_str("]\n");
+            }
+        }
+        out
+    }
+
+    /// write the records to `path`
+    pub fn save(&self) -> io::Result<()> {
+        std::fs::write(&self.path, self.contents())
+    }
+}
+
+pub(crate) fn record_frame_start(
+    time: Res<Time<Real>>,
+    pacing: Res<QuickResponsePacing>,
+    wake_reason: Res<WakeReason>,
+    recorder: Option<ResMut<FrameRecorder>>,
+) {
+    let Some(mut recorder) = recorder else {
+        return;
+    };
+
+    recorder.current = Some(FrameRecord {
+        timestamp: time.elapsed(),
+        interval: time.delta(),
+        target: target_interval(&pacing),
+        mode: mode_name(pacing.mode),
+        boosted: pacing.is_continuous(),
+        wake_reason: *wake_reason,
+    });
+}
+
+pub(crate) fn record_frame_end(
+    recorder: Option<ResMut<FrameRecorder>>,
+    mut exits: MessageReader<AppExit>,
+) {
+    let exiting = exits.read().count() > 0;
+
+    let Some(mut recorder) = recorder else {
+        return;
+    };
+
+    if let Some(record) = recorder.current.take() {
+        recorder.records.push(record);
+    }
+
+    if exiting && recorder.write_on_exit {
+        if let Err(err) = recorder.save() {
+            error!("bevy_quick_response: failed to write frame records to {:?}: {err}", recorder.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{time::TimeUpdateStrategy, window::CursorMoved};
+
+    use crate::QuickResponsePlugin;
+
+    use super::*;
+
+    #[test]
+    fn test_frame_recorder() {
+        let path = std::env::temp_dir().join(format!("bevy_quick_response_test_{}.csv", std::process::id()));
+
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(10)))
+            .insert_resource(FrameRecorder::new(&path, FrameRecordFormat::Csv))
+            .add_plugins(QuickResponsePlugin::auto_no_vsync(60.0, 120.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+
+        app.update();
+        let window = app.world_mut().spawn_empty().id();
+        app.world_mut().write_message(CursorMoved { window, position: Vec2::ZERO, delta: None });
+        app.update();
+        app.world_mut().write_message(AppExit::Success);
+        app.update();
+
+        let recorder = app.world().resource::<FrameRecorder>();
+        assert_eq!(recorder.records().len(), 3);
+        assert_eq!(recorder.records()[0].mode, "AutoNoVsync");
+        assert_eq!(recorder.records()[1].interval, Duration::from_millis(10));
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written.lines().count(), 4);
+        assert!(written.starts_with("timestamp,interval,target,mode,boosted,wake_reason\n"));
+
+        let mut recorder = recorder.clone();
+        recorder.format = FrameRecordFormat::Json;
+        assert!(recorder.contents().contains("\"mode\":\"AutoNoVsync\""));
+    }
+}
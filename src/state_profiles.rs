@@ -0,0 +1,148 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_framepace::FramepaceSettings;
+
+use crate::{QuickResponseMode, QuickResponsePacing, delta_smoothing_settings_for_mode, is_competitive_on_cursor_grab, limiter_for_mode, present_mode_for_mode, winit_settings_for_mode, winit_settings_policy_for_mode};
+use crate::winit_policy::{WinitSettingsTracker, resolve_winit_settings};
+
+/// Per-state pacing profiles: switch the [`QuickResponseMode`] when entering a state
+/// (winit settings, frame limiter, and present mode of the primary window).
+///
+/// requires [`QuickResponsePlugin`](crate::QuickResponsePlugin) (not in `QuickResponseMode::None`).
+/// `auto_init_default_plugins` of the profiles is ignored.
+///
+/// ```ignore
+/// app.add_plugins(QuickResponseStatePlugin::<GameState>::new()
+///     .profile(GameState::MainMenu, QuickResponsePlugin::power_saving(30.0).mode)
+///     .profile(GameState::InGame, QuickResponsePlugin::immediate(60.0, 240.0).mode));
+/// ```
+pub struct QuickResponseStatePlugin<S: States> {
+    profiles: Vec<(S, QuickResponseMode)>,
+}
+
+impl<S: States> QuickResponseStatePlugin<S> {
+    pub fn new() -> Self {
+        QuickResponseStatePlugin {
+            profiles: Vec::new(),
+        }
+    }
+
+    /// use the mode while in the state
+    pub fn profile(mut self, state: S, mode: QuickResponseMode) -> Self {
+        self.profiles.retain(|(s, _)| *s != state);
+        self.profiles.push((state, mode));
+        self
+    }
+}
+
+impl<S: States> Default for QuickResponseStatePlugin<S> {
+    fn default() -> Self {
+        QuickResponseStatePlugin::new()
+    }
+}
+
+impl<S: States> Plugin for QuickResponseStatePlugin<S> {
+    fn build(&self, app: &mut App) {
+        for (state, mode) in self.profiles.iter().cloned() {
+            app.add_systems(OnEnter(state), move |world: &mut World| set_mode(world, mode));
+        }
+    }
+}
+
+/// switch the mode at runtime
+pub(crate) fn set_mode(world: &mut World, mode: QuickResponseMode) {
+    // the settings of the user or other plugins are resolved by the policy of the new mode
+    let existing = world.get_resource::<WinitSettingsTracker>().and_then(|tracker| tracker.existing.clone());
+    let winit_settings = resolve_winit_settings(winit_settings_policy_for_mode(mode), existing.as_ref(), winit_settings_for_mode(mode));
+
+    let Some(mut pacing) = world.get_resource_mut::<QuickResponsePacing>() else {
+        return;
+    };
+
+    let present_mode = present_mode_for_mode(mode);
+    let refresh_rate = pacing.refresh_rate;
+    pacing.mode = mode;
+    pacing.base_winit_settings = winit_settings;
+    pacing.base_present_mode = present_mode;
+
+    // keep Immediate while the competitive mode continues
+    let keep_present_mode = pacing.cursor_grabbed && is_competitive_on_cursor_grab(mode);
+
+    world.insert_resource(delta_smoothing_settings_for_mode(mode));
+
+    if let Some(mut framepace_settings) = world.get_resource_mut::<FramepaceSettings>() {
+        framepace_settings.limiter = limiter_for_mode(mode, refresh_rate);
+    }
+
+    if !keep_present_mode {
+        let mut windows = world.query_filtered::<&mut Window, With<PrimaryWindow>>();
+        if let Ok(mut window) = windows.single_mut(world) {
+            if window.present_mode != present_mode {
+                window.present_mode = present_mode;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{state::app::StatesPlugin, window::PresentMode, winit::{UpdateMode, WinitSettings}};
+
+    use crate::{QuickResponsePlugin, WinitSettingsPolicy};
+
+    use super::*;
+
+    #[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)]
+    enum TestState {
+        #[default]
+        MainMenu,
+        InGame,
+    }
+
+    #[test]
+    fn test_state_profiles() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(StatesPlugin)
+            .init_state::<TestState>()
+            .add_plugins(QuickResponsePlugin::fast_vsync(60.0, 120.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test())
+            .add_plugins(QuickResponseStatePlugin::<TestState>::new()
+                .profile(TestState::MainMenu, QuickResponsePlugin::power_saving(30.0).mode)
+                .profile(TestState::InGame, QuickResponsePlugin::immediate(30.0, 240.0).mode));
+
+        let window = app.world_mut().spawn((Window::default(), PrimaryWindow)).id();
+
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+        assert_matches!(app.world().resource::<QuickResponsePacing>().mode, QuickResponseMode::PowerSaving(_));
+
+        app.world_mut().resource_mut::<NextState<TestState>>().set(TestState::InGame);
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::reactive_low_power(Duration::from_secs_f64(1.0 / 30.0)));
+        assert_eq!(app.world().get::<Window>(window).unwrap().present_mode, PresentMode::Immediate);
+    }
+
+    #[test]
+    fn test_state_profiles_keep_existing_winit_settings() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(StatesPlugin)
+            .init_state::<TestState>()
+            .insert_resource(WinitSettings::desktop_app())
+            .add_plugins(QuickResponsePlugin::fast_vsync(60.0, 120.0)
+                .with_winit_settings_policy(WinitSettingsPolicy::KeepExisting)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test())
+            .add_plugins(QuickResponseStatePlugin::<TestState>::new()
+                .profile(TestState::MainMenu, QuickResponsePlugin::immediate(30.0, 240.0)
+                    .with_winit_settings_policy(WinitSettingsPolicy::KeepExisting).mode));
+
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+    }
+}
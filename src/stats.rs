@@ -0,0 +1,210 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::QuickResponsePacing;
+
+/// Statistics of the last frame intervals (a ring buffer of `capacity` frames).
+///
+/// inserted as a resource by [`QuickResponsePlugin`](crate::QuickResponsePlugin),
+/// insert your own before adding the plugin to change the capacity.
+/// percentiles and lows sort a copy of the intervals, so call them once per frame at most (for example: in a HUD).
+#[derive(Resource, Debug, Clone)]
+pub struct FramePacingStats {
+    capacity: usize,
+    intervals: VecDeque<Duration>,
+    /// target interval of each frame (None while not boosted: the app waits for input)
+    targets: VecDeque<Option<Duration>>,
+}
+
+impl Default for FramePacingStats {
+    fn default() -> Self {
+        FramePacingStats::with_capacity(1000)
+    }
+}
+
+impl FramePacingStats {
+    pub fn with_capacity(capacity: usize) -> Self {
+        FramePacingStats {
+            capacity: capacity.max(1),
+            intervals: VecDeque::with_capacity(capacity),
+            targets: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.intervals.clear();
+        self.targets.clear();
+    }
+
+    /// add a frame interval (the oldest one is dropped when full)
+    pub fn push(&mut self, interval: Duration, target: Option<Duration>) {
+        if self.intervals.len() == self.capacity {
+            self.intervals.pop_front();
+            self.targets.pop_front();
+        }
+        self.intervals.push_back(interval);
+        self.targets.push_back(target);
+    }
+
+    /// frame intervals, oldest first
+    pub fn intervals(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.intervals.iter().copied()
+    }
+
+    /// the last frame interval
+    pub fn last(&self) -> Option<Duration> {
+        self.intervals.back().copied()
+    }
+
+    pub fn mean(&self) -> Option<Duration> {
+        if self.intervals.is_empty() {
+            return None;
+        }
+        Some(self.intervals.iter().sum::<Duration>() / self.intervals.len() as u32)
+    }
+
+    /// mean fps
+    pub fn fps(&self) -> Option<f64> {
+        self.mean().filter(|mean| !mean.is_zero()).map(|mean| 1.0 / mean.as_secs_f64())
+    }
+
+    fn sorted(&self) -> Vec<Duration> {
+        let mut sorted: Vec<_> = self.intervals.iter().copied().collect();
+        sorted.sort_unstable();
+        sorted
+    }
+
+    /// the interval at the percentile (0.0 - 100.0, nearest rank). for example: 99.0 for 99th percentile
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        let sorted = self.sorted();
+        if sorted.is_empty() {
+            return None;
+        }
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.clamp(1, sorted.len()) - 1])
+    }
+
+    /// fps of the slowest `percent` of frames (mean of their intervals)
+    pub fn low_fps(&self, percent: f64) -> Option<f64> {
+        let sorted = self.sorted();
+        if sorted.is_empty() {
+            return None;
+        }
+        let count = ((percent.clamp(0.0, 100.0) / 100.0 * sorted.len() as f64).ceil() as usize).max(1);
+        let slowest = &sorted[sorted.len() - count..];
+        let mean = slowest.iter().sum::<Duration>() / count as u32;
+        (!mean.is_zero()).then(|| 1.0 / mean.as_secs_f64())
+    }
+
+    /// 1% low fps
+    pub fn one_percent_low(&self) -> Option<f64> {
+        self.low_fps(1.0)
+    }
+
+    /// 0.1% low fps
+    pub fn point_one_percent_low(&self) -> Option<f64> {
+        self.low_fps(0.1)
+    }
+
+    /// root mean square deviation of the intervals from the target interval
+    /// (only frames with a target, i.e. while boosted)
+    pub fn jitter(&self) -> Option<Duration> {
+        let deviations: Vec<f64> = self.intervals.iter().zip(self.targets.iter())
+            .filter_map(|(interval, target)| target.map(|target| interval.as_secs_f64() - target.as_secs_f64()))
+            .collect();
+        if deviations.is_empty() {
+            return None;
+        }
+        let variance = deviations.iter().map(|d| d * d).sum::<f64>() / deviations.len() as f64;
+        Some(Duration::from_secs_f64(variance.sqrt()))
+    }
+}
+
+/// target interval of the next frame, decided by the pacing of the previous frame
+pub(crate) fn target_interval(pacing: &QuickResponsePacing) -> Option<Duration> {
+    if let Some(fps) = pacing.ramp_fps {
+        return Some(Duration::from_secs_f64(1.0 / fps));
+    }
+    if !pacing.is_continuous() {
+        return None;
+    }
+    pacing.effective_max_fps()
+        .filter(|fps| *fps > 0.0)
+        .map(|fps| Duration::from_secs_f64(1.0 / fps))
+}
+
+pub(crate) fn record_frame_stats(
+    time: Res<Time<Real>>,
+    pacing: Res<QuickResponsePacing>,
+    mut stats: ResMut<FramePacingStats>,
+) {
+    // the first update has no interval
+    if time.delta().is_zero() {
+        return;
+    }
+    stats.push(time.delta(), target_interval(&pacing));
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::time::TimeUpdateStrategy;
+
+    use crate::{KeepAwake, QuickResponsePlugin};
+
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let mut stats = FramePacingStats::with_capacity(4);
+        assert_eq!(stats.mean(), None);
+
+        for ms in [10, 10, 20, 10, 30] {
+            stats.push(Duration::from_millis(ms), Some(Duration::from_millis(10)));
+        }
+        // the first one is dropped
+        assert_eq!(stats.len(), 4);
+        assert_eq!(stats.mean(), Some(Duration::from_millis(70) / 4));
+        assert_eq!(stats.percentile(50.0), Some(Duration::from_millis(10)));
+        assert_eq!(stats.percentile(99.0), Some(Duration::from_millis(30)));
+        assert_matches!(stats.one_percent_low(), Some(fps) if (fps - 1.0 / 0.03).abs() < 0.001);
+
+        let jitter = stats.jitter().unwrap().as_secs_f64();
+        let expected = ((0.0 + 0.01 * 0.01 + 0.0 + 0.02 * 0.02) / 4.0_f64).sqrt();
+        assert!((jitter - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_record_frame_stats() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(5)))
+            .add_plugins(QuickResponsePlugin::fast_vsync(60.0, 200.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+
+        app.world_mut().spawn(KeepAwake);
+        for _ in 0..5 {
+            app.update();
+        }
+
+        let stats = app.world().resource::<FramePacingStats>();
+        assert!(!stats.is_empty());
+        assert_eq!(stats.last(), Some(Duration::from_millis(5)));
+        // boosted at 200 fps, exactly on target
+        assert_eq!(stats.jitter(), Some(Duration::ZERO));
+    }
+}
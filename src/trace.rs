@@ -0,0 +1,197 @@
+use std::{fmt::Write as _, io, path::PathBuf, time::Duration};
+
+use bevy::{platform::time::Instant, prelude::*};
+
+use crate::{QuickResponsePacing, WakeReason, input_messages::InputMessages, recorder::mode_name};
+
+#[derive(Debug, Clone, PartialEq)]
+struct TraceEvent {
+    name: &'static str,
+    start: Duration,
+    /// None for instant events
+    duration: Option<Duration>,
+    args: String,
+}
+
+/// Opt-in pacing timeline in Chrome trace event format (JSON), for `chrome://tracing` or Perfetto.
+/// insert it as a resource to start tracing; the trace is written to `path` on exit
+/// (if `write_on_exit`), or on demand by [`save`](Self::save).
+///
+/// spans:
+/// - `update`: from the start of `First` until the end of the pacing systems in `Last`
+/// - `render + framepace`: the gap between updates while boosted: extract, render and present, and the sleep of
+///   bevy_framepace (the sleep alone is not measured, as the limiter runs in the render app)
+/// - `winit wait`: the gap between updates while not boosted (the event loop waits for input or a timeout)
+/// - instant events for the input received in each update
+///
+/// ```ignore
+/// app.insert_resource(PacingTrace::new("pacing_trace.json"));
+/// ```
+#[derive(Resource, Debug, Clone)]
+pub struct PacingTrace {
+    pub path: PathBuf,
+    /// default: true
+    pub write_on_exit: bool,
+    origin: Option<Instant>,
+    frame_start: Option<Instant>,
+    /// end of the previous update, and whether the app was boosted then
+    last_end: Option<(Instant, bool)>,
+    events: Vec<TraceEvent>,
+}
+
+impl PacingTrace {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        PacingTrace {
+            path: path.into(),
+            write_on_exit: true,
+            origin: None,
+            frame_start: None,
+            last_end: None,
+            events: Vec::new(),
+        }
+    }
+
+    /// number of recorded events
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    fn since_origin(&mut self, instant: Instant) -> Duration {
+        let origin = *self.origin.get_or_insert(instant);
+        instant.saturating_duration_since(origin)
+    }
+
+    fn push_span(&mut self, name: &'static str, start: Instant, end: Instant, args: String) {
+        let start_time = self.since_origin(start);
+        self.events.push(TraceEvent {
+            name,
+            start: start_time,
+            duration: Some(end.saturating_duration_since(start)),
+            args,
+        });
+    }
+
+    /// the trace in Chrome trace event format (JSON)
+    pub fn contents(&self) -> String {
+        let mut out = String::from("{\"displayTimeUnit\":\"ms\",\"traceEvents\":[\n");
+        for (i, event) in self.events.iter().enumerate() {
+            let _ = write!(out, "  {{\"name\":\"{}\",\"pid\":1,\"tid\":1,\"ts\":{:.3}", event.name, event.start.as_secs_f64() * 1_000_000.0);
+            match event.duration {
+                Some(duration) => {
+                    let _ = write!(out, ",\"ph\":\"X\",\"dur\":{:.3}", duration.as_secs_f64() * 1_000_000.0);
+                }
+                None => out.push_str(",\"ph\":\"i\",\"s\":\"t\""),
+            }
+            let _ = write!(out, ",\"args\":{{{}}}}}", event.args);
+            out.push_str(if i + 1 < self.events.len() { ",\n" } else { "\n" });
+        }
+        out.push_str("]}\n");
+        out
+    }
+
+    /// write the trace to `path`
+    pub fn save(&self) -> io::Result<()> {
+        std::fs::write(&self.path, self.contents())
+    }
+}
+
+pub(crate) fn trace_frame_start(trace: Option<ResMut<PacingTrace>>) {
+    let Some(mut trace) = trace else {
+        return;
+    };
+
+    let now = Instant::now();
+    if let Some((last_end, boosted)) = trace.last_end.take() {
+        let name = if boosted { "render + framepace" } else { "winit wait" };
+        trace.push_span(name, last_end, now, String::new());
+    }
+    trace.frame_start = Some(now);
+}
+
+pub(crate) fn trace_frame_end(
+    pacing: Res<QuickResponsePacing>,
+    wake_reason: Res<WakeReason>,
+    trace: Option<ResMut<PacingTrace>>,
+    mut inputs: InputMessages,
+    mut exits: MessageReader<AppExit>,
+) {
+    let kinds = inputs.read_kinds();
+    let exiting = exits.read().count() > 0;
+
+    let Some(mut trace) = trace else {
+        return;
+    };
+
+    let now = Instant::now();
+    let boosted = pacing.is_continuous();
+    if let Some(start) = trace.frame_start.take() {
+        let start_time = trace.since_origin(start);
+        for kind in kinds {
+            trace.events.push(TraceEvent {
+                name: "input",
+                start: start_time,
+                duration: None,
+                args: format!("\"kind\":\"{kind}\""),
+            });
+        }
+        let args = format!("\"mode\":\"{}\",\"boosted\":{},\"wake_reason\":\"{}\"", mode_name(pacing.mode), boosted, wake_reason.name());
+        trace.push_span("update", start, now, args);
+    }
+    trace.last_end = Some((now, boosted));
+
+    if exiting && trace.write_on_exit {
+        if let Err(err) = trace.save() {
+            error!("bevy_quick_response: failed to write pacing trace to {:?}: {err}", trace.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::keyboard::{Key, KeyCode, KeyboardInput};
+    use bevy::input::ButtonState;
+
+    use crate::QuickResponsePlugin;
+
+    use super::*;
+
+    #[test]
+    fn test_pacing_trace() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .insert_resource(PacingTrace::new(std::env::temp_dir().join("unused.json")))
+            .add_plugins(QuickResponsePlugin::power_saving(60.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+
+        app.update();
+        let window = app.world_mut().spawn_empty().id();
+        app.world_mut().write_message(KeyboardInput {
+            key_code: KeyCode::KeyA,
+            logical_key: Key::Character("a".into()),
+            state: ButtonState::Pressed,
+            text: None,
+            repeat: false,
+            window,
+        });
+        app.update();
+
+        let trace = app.world().resource::<PacingTrace>();
+        let names: Vec<_> = trace.events.iter().map(|event| event.name).collect();
+        assert_eq!(names, vec!["update", "winit wait", "input", "update"]);
+
+        let contents = trace.contents();
+        assert!(contents.starts_with("{\"displayTimeUnit\":\"ms\",\"traceEvents\":["));
+        assert!(contents.contains("\"kind\":\"keyboard\""));
+        assert!(contents.contains("\"mode\":\"PowerSaving\""));
+    }
+}
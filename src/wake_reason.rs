@@ -0,0 +1,176 @@
+use bevy::{diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic}, input::mouse::MouseMotion, platform::collections::HashMap, prelude::*, window::{RequestRedraw, WindowEvent}, winit::{UpdateMode, WinitSettings}};
+
+use crate::{QuickResponsePacing, QuickResponseWaker};
+
+/// Why the current frame was run (updated at the start of each frame).
+/// counted in [`WakeReasonCounts`], and reported to diagnostics (see [`WakeReason::diagnostic_path`]).
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WakeReason {
+    /// the first frame
+    #[default]
+    Startup,
+    /// window events (for example: cursor moved, keyboard input, resized)
+    WindowEvent,
+    /// device events (raw mouse motion), if the update mode reacts to them
+    DeviceEvent,
+    /// [`QuickResponseWaker`] or a `RequestRedraw` message
+    UserWake,
+    /// the wait of the reactive update mode expired (base fps, scheduled wake-ups, polling)
+    Timer,
+    /// the app was boosted (continuous at max fps)
+    Boost,
+    /// the configured update mode itself is continuous
+    Continuous,
+}
+
+impl WakeReason {
+    pub const ALL: [WakeReason; 7] = [
+        WakeReason::Startup,
+        WakeReason::WindowEvent,
+        WakeReason::DeviceEvent,
+        WakeReason::UserWake,
+        WakeReason::Timer,
+        WakeReason::Boost,
+        WakeReason::Continuous,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WakeReason::Startup => "startup",
+            WakeReason::WindowEvent => "window_event",
+            WakeReason::DeviceEvent => "device_event",
+            WakeReason::UserWake => "user_wake",
+            WakeReason::Timer => "timer",
+            WakeReason::Boost => "boost",
+            WakeReason::Continuous => "continuous",
+        }
+    }
+
+    /// diagnostic of the reason: 1.0 for frames woken by the reason, 0.0 otherwise
+    /// (so the average is the share of frames).
+    pub fn diagnostic_path(&self) -> DiagnosticPath {
+        DiagnosticPath::new(format!("quick_response/wake/{}", self.name()))
+    }
+}
+
+/// Number of frames for each [`WakeReason`] since startup (or since cleared).
+#[derive(Resource, Debug, Clone, Default)]
+pub struct WakeReasonCounts {
+    counts: HashMap<WakeReason, u64>,
+}
+
+impl WakeReasonCounts {
+    pub fn get(&self, reason: WakeReason) -> u64 {
+        self.counts.get(&reason).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    pub fn clear(&mut self) {
+        self.counts.clear();
+    }
+}
+
+pub(crate) fn register_wake_diagnostics(app: &mut App) {
+    for reason in WakeReason::ALL {
+        app.register_diagnostic(Diagnostic::new(reason.diagnostic_path()));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn detect_wake_reason(
+    winit_settings: Res<WinitSettings>,
+    windows: Query<&Window>,
+    pacing: Res<QuickResponsePacing>,
+    waker: Res<QuickResponseWaker>,
+    mut window_events: MessageReader<WindowEvent>,
+    mut redraw_requests: MessageReader<RequestRedraw>,
+    mut mouse_motions: MessageReader<MouseMotion>,
+    mut wake_reason: ResMut<WakeReason>,
+    mut counts: ResMut<WakeReasonCounts>,
+    mut diagnostics: Diagnostics,
+    mut started: Local<bool>,
+) {
+    let window_event = window_events.read().count() > 0;
+    let redraw_requested = redraw_requests.read().count() > 0;
+    let device_event = mouse_motions.read().count() > 0;
+    let user_wake = waker.take_woken() || redraw_requested;
+
+    // winit settings still hold what the previous frame applied, i.e. what decided the wait before this frame
+    let focused = windows.iter().any(|window| window.focused);
+    let reason = if !*started {
+        *started = true;
+        WakeReason::Startup
+    } else if winit_settings.update_mode(focused) == UpdateMode::Continuous {
+        if pacing.base_winit_settings.update_mode(focused) == UpdateMode::Continuous {
+            WakeReason::Continuous
+        } else {
+            WakeReason::Boost
+        }
+    } else if user_wake {
+        WakeReason::UserWake
+    } else if window_event {
+        WakeReason::WindowEvent
+    } else if device_event {
+        WakeReason::DeviceEvent
+    } else {
+        WakeReason::Timer
+    };
+
+    if *wake_reason != reason {
+        *wake_reason = reason;
+    }
+    *counts.counts.entry(reason).or_insert(0) += 1;
+
+    for r in WakeReason::ALL {
+        diagnostics.add_measurement(&r.diagnostic_path(), || if r == reason { 1.0 } else { 0.0 });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::diagnostic::DiagnosticsStore;
+
+    use crate::{KeepAwake, QuickResponsePlugin};
+
+    use super::*;
+
+    #[test]
+    fn test_wake_reason() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(QuickResponsePlugin::power_saving(60.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+        let window = app.world_mut().spawn(Window { focused: true, ..default() }).id();
+
+        app.update();
+        assert_eq!(*app.world().resource::<WakeReason>(), WakeReason::Startup);
+
+        app.update();
+        assert_eq!(*app.world().resource::<WakeReason>(), WakeReason::Timer);
+
+        app.world_mut().write_message(RequestRedraw);
+        app.update();
+        assert_eq!(*app.world().resource::<WakeReason>(), WakeReason::UserWake);
+
+        app.world_mut().write_message(WindowEvent::CursorLeft(bevy::window::CursorLeft { window }));
+        app.update();
+        assert_eq!(*app.world().resource::<WakeReason>(), WakeReason::WindowEvent);
+
+        app.world_mut().spawn(KeepAwake);
+        app.update();
+        app.update();
+        assert_eq!(*app.world().resource::<WakeReason>(), WakeReason::Boost);
+
+        let counts = app.world().resource::<WakeReasonCounts>();
+        assert_eq!(counts.total(), 6);
+        assert_eq!(counts.get(WakeReason::Timer), 2);
+
+        let store = app.world().resource::<DiagnosticsStore>();
+        assert_eq!(store.get(&WakeReason::Boost.diagnostic_path()).and_then(|d| d.value()), Some(1.0));
+    }
+}
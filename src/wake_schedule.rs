@@ -0,0 +1,170 @@
+use std::{borrow::Cow, time::Duration};
+
+use bevy::{platform::{collections::HashMap, time::Instant}, prelude::*};
+
+use crate::QuickResponsePacing;
+
+/// Scheduled wake-ups (for example: cursor blink, toast timeouts, clock updates).
+/// the plugin shortens the wait of the reactive update modes so that the app sleeps until the next deadline,
+/// instead of polling with a fixed interval.
+///
+/// inserted as a resource by [`QuickResponsePlugin`](crate::QuickResponsePlugin).
+#[derive(Resource, Debug, Default, Clone)]
+pub struct WakeSchedule {
+    /// one-shot wake-ups, removed after the deadline passed
+    deadlines: Vec<Instant>,
+    /// named periodic wake-ups, kept until cancelled
+    intervals: HashMap<Cow<'static, str>, WakeInterval>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WakeInterval {
+    period: Duration,
+    next: Instant,
+}
+
+impl WakeSchedule {
+    /// wake the app at the given instant (once)
+    pub fn wake_at(&mut self, at: Instant) {
+        self.deadlines.push(at);
+    }
+
+    /// wake the app after the given delay from now (once)
+    pub fn wake_after(&mut self, delay: Duration) {
+        self.wake_at(Instant::now() + delay);
+    }
+
+    /// wake the app every `period` until [`WakeSchedule::cancel`] is called with the same name.
+    /// registering the same name again replaces the period.
+    pub fn wake_every(&mut self, name: impl Into<Cow<'static, str>>, period: Duration) {
+        let name = name.into();
+        match self.intervals.get_mut(&name) {
+            Some(interval) if interval.period == period => {}
+            _ => {
+                self.intervals.insert(name, WakeInterval {
+                    period,
+                    next: Instant::now() + period,
+                });
+            }
+        }
+    }
+
+    /// cancel a periodic wake-up registered with [`WakeSchedule::wake_every`]
+    pub fn cancel(&mut self, name: &str) {
+        self.intervals.remove(name);
+    }
+
+    /// true if a periodic wake-up with the given name is registered
+    pub fn is_scheduled(&self, name: &str) -> bool {
+        self.intervals.contains_key(name)
+    }
+
+    /// remove all scheduled wake-ups
+    pub fn clear(&mut self) {
+        self.deadlines.clear();
+        self.intervals.clear();
+    }
+
+    /// the earliest scheduled wake-up
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.deadlines.iter().copied()
+            .chain(self.intervals.values().map(|interval| interval.next))
+            .min()
+    }
+
+    /// drop passed one-shot deadlines, and advance passed periodic ones
+    fn advance(&mut self, now: Instant) {
+        self.deadlines.retain(|deadline| *deadline > now);
+
+        for interval in self.intervals.values_mut() {
+            if interval.next <= now {
+                interval.next += interval.period;
+                // do not try to catch up after a long sleep
+                if interval.next <= now {
+                    interval.next = now + interval.period;
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn detect_wake_schedule(
+    mut schedule: ResMut<WakeSchedule>,
+    time: Res<Time<Real>>,
+    mut pacing: ResMut<QuickResponsePacing>,
+) {
+    let now = Instant::now();
+
+    // only mutate when something has passed, to keep change detection meaningful
+    if schedule.next_deadline().is_some_and(|deadline| deadline <= now) {
+        schedule.advance(now);
+    }
+
+    // winit measures the wait from the start of the update
+    let update_start = time.last_update().unwrap_or(now);
+    let wake_in = schedule.next_deadline()
+        .map(|deadline| deadline.saturating_duration_since(update_start));
+
+    if pacing.wake_in != wake_in {
+        pacing.wake_in = wake_in;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::winit::{UpdateMode, WinitSettings};
+
+    use crate::QuickResponsePlugin;
+
+    use super::*;
+
+    fn wait_of(mode: UpdateMode) -> Duration {
+        match mode {
+            UpdateMode::Reactive { wait, .. } => wait,
+            UpdateMode::Continuous => Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_wake_schedule() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(QuickResponsePlugin::power_saving(60.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+
+        app.update();
+        assert_eq!(wait_of(app.world().resource::<WinitSettings>().focused_mode), Duration::from_secs(5));
+
+        app.world_mut().resource_mut::<WakeSchedule>().wake_after(Duration::from_millis(500));
+        app.update();
+        assert!(wait_of(app.world().resource::<WinitSettings>().focused_mode) <= Duration::from_millis(500));
+        assert!(wait_of(app.world().resource::<WinitSettings>().unfocused_mode) <= Duration::from_millis(500));
+
+        app.world_mut().resource_mut::<WakeSchedule>().clear();
+        app.update();
+        assert_eq!(wait_of(app.world().resource::<WinitSettings>().focused_mode), Duration::from_secs(5));
+        assert_eq!(wait_of(app.world().resource::<WinitSettings>().unfocused_mode), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_wake_schedule_advance() {
+        let now = Instant::now();
+        let mut schedule = WakeSchedule::default();
+
+        schedule.wake_at(now + Duration::from_millis(100));
+        schedule.wake_every("blink", Duration::from_millis(500));
+        assert!(schedule.is_scheduled("blink"));
+        assert_eq!(schedule.next_deadline(), Some(now + Duration::from_millis(100)));
+
+        schedule.advance(now + Duration::from_millis(200));
+        assert!(schedule.next_deadline().is_some_and(|deadline| deadline > now + Duration::from_millis(200)));
+
+        schedule.advance(now + Duration::from_secs(10));
+        assert!(schedule.next_deadline().is_some_and(|deadline| deadline > now + Duration::from_secs(10)));
+
+        schedule.cancel("blink");
+        assert_eq!(schedule.next_deadline(), None);
+    }
+}
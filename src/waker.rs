@@ -0,0 +1,128 @@
+use std::{sync::{Arc, Mutex, OnceLock, atomic::{AtomicBool, Ordering}}, time::Duration};
+
+use bevy::{platform::time::Instant, prelude::*, winit::{EventLoopProxy, EventLoopProxyWrapper, WinitUserEvent}};
+
+use crate::QuickResponsePacing;
+
+/// Cloneable handle to request a frame from any thread (for example: worker threads, sockets, file watchers).
+/// useful in `PowerSaving` mode, where the app otherwise waits for user input.
+///
+/// inserted as a resource by [`QuickResponsePlugin`](crate::QuickResponsePlugin).
+/// clone it in a system (or from the world) and move the clone to another thread.
+#[derive(Resource, Clone, Default)]
+pub struct QuickResponseWaker {
+    inner: Arc<WakerInner>,
+}
+
+#[derive(Default)]
+struct WakerInner {
+    proxy: OnceLock<EventLoopProxy<WinitUserEvent>>,
+    boost_until: Mutex<Option<Instant>>,
+    /// set by wake(), for [`WakeReason`](crate::WakeReason)
+    woken: AtomicBool,
+}
+
+impl std::fmt::Debug for QuickResponseWaker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuickResponseWaker")
+            .field("connected", &self.inner.proxy.get().is_some())
+            .finish()
+    }
+}
+
+impl QuickResponseWaker {
+    /// request an immediate frame.
+    /// returns false if the event loop is not available (no WinitPlugin, or the app has exited).
+    pub fn wake(&self) -> bool {
+        match self.inner.proxy.get() {
+            Some(proxy) => {
+                self.inner.woken.store(true, Ordering::Relaxed);
+                proxy.send_event(WinitUserEvent::WakeUp).is_ok()
+            }
+            None => false,
+        }
+    }
+
+    /// request an immediate frame, and keep the app continuous at max fps for the given duration.
+    /// returns false if the event loop is not available (the boost is applied on the next frame anyway).
+    pub fn wake_with_boost(&self, duration: Duration) -> bool {
+        let until = Instant::now() + duration;
+
+        if let Ok(mut boost_until) = self.inner.boost_until.lock() {
+            if boost_until.is_none_or(|current| current < until) {
+                *boost_until = Some(until);
+            }
+        }
+
+        self.wake()
+    }
+
+    pub(crate) fn connect(&self, proxy: EventLoopProxy<WinitUserEvent>) {
+        let _ = self.inner.proxy.set(proxy);
+    }
+
+    pub(crate) fn take_woken(&self) -> bool {
+        self.inner.woken.swap(false, Ordering::Relaxed)
+    }
+
+    fn take_boost(&self) -> Option<Instant> {
+        self.inner.boost_until.lock().ok()?.take()
+    }
+}
+
+/// connect the waker to the winit event loop, if WinitPlugin is added.
+/// called from `Plugin::finish`, so the order of plugins does not matter.
+pub(crate) fn connect_waker(app: &mut App) {
+    let Some(proxy) = app.world().get_resource::<EventLoopProxyWrapper>() else {
+        return;
+    };
+    let proxy = (**proxy).clone();
+
+    if let Some(waker) = app.world().get_resource::<QuickResponseWaker>() {
+        waker.connect(proxy);
+    }
+}
+
+pub(crate) fn detect_waker_boost(
+    waker: Res<QuickResponseWaker>,
+    mut pacing: ResMut<QuickResponsePacing>,
+) {
+    if let Some(until) = waker.take_boost() {
+        pacing.request_boost_until(until);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::winit::{UpdateMode, WinitSettings};
+
+    use crate::QuickResponsePlugin;
+
+    use super::*;
+
+    #[test]
+    fn test_waker_boost_from_thread() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(QuickResponsePlugin::power_saving(60.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+        app.finish();
+
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+
+        let waker = app.world().resource::<QuickResponseWaker>().clone();
+        let woke = std::thread::spawn(move || waker.wake_with_boost(Duration::from_secs(60)))
+            .join()
+            .unwrap();
+
+        // no event loop in tests
+        assert!(!woke);
+
+        app.update();
+        assert!(app.world().resource::<QuickResponsePacing>().is_boosted());
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::Continuous);
+    }
+}
@@ -0,0 +1,156 @@
+use bevy::{prelude::*, winit::{UpdateMode, WinitSettings}};
+
+/// How to treat `WinitSettings` which already exist when the plugin is added
+/// (inserted by the user, or by other plugins such as UI frameworks).
+///
+/// settings inserted after the plugin and before the first update (for example: by plugins added later)
+/// are treated the same way, and so are the settings of each mode switched by
+/// [`QuickResponseStatePlugin`](crate::QuickResponseStatePlugin).
+/// settings equal to `WinitSettings::default()` (inserted by `WinitPlugin`) are not regarded as existing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WinitSettingsPolicy {
+    /// replace the existing settings with the settings of the mode
+    #[default]
+    Overwrite,
+    /// keep the existing settings (boosts still switch to continuous update)
+    KeepExisting,
+    /// keep the existing update modes, but use the wait durations of the mode
+    /// (where both are reactive)
+    MergeWaitDurations,
+}
+
+/// `WinitSettings` seen by the plugin, to tell its own writes from the settings of the user or other plugins
+#[derive(Resource, Debug, Clone)]
+pub(crate) struct WinitSettingsTracker {
+    /// settings inserted or changed outside of the plugin (resolved by the policy on mode switches)
+    pub existing: Option<WinitSettings>,
+    /// update modes written by the plugin (at build, then by `apply_pacing`)
+    pub applied: (UpdateMode, UpdateMode),
+    /// true after the first update
+    pub started: bool,
+}
+
+impl WinitSettingsTracker {
+    pub fn new(existing: Option<&WinitSettings>, applied: &WinitSettings) -> Self {
+        WinitSettingsTracker {
+            existing: existing.cloned(),
+            applied: (applied.focused_mode, applied.unfocused_mode),
+            started: false,
+        }
+    }
+}
+
+fn merge_wait(existing: UpdateMode, ours: UpdateMode) -> UpdateMode {
+    match (existing, ours) {
+        (UpdateMode::Reactive { react_to_device_events, react_to_user_events, react_to_window_events, .. }, UpdateMode::Reactive { wait, .. }) => {
+            UpdateMode::Reactive { wait, react_to_device_events, react_to_user_events, react_to_window_events }
+        }
+        _ => existing,
+    }
+}
+
+/// resolve the settings to insert, warning when the existing settings conflict with the mode
+pub(crate) fn resolve_winit_settings(
+    policy: WinitSettingsPolicy,
+    existing: Option<&WinitSettings>,
+    ours: WinitSettings,
+) -> WinitSettings {
+    let default_settings = WinitSettings::default();
+    let Some(existing) = existing.filter(|existing| {
+        existing.focused_mode != default_settings.focused_mode || existing.unfocused_mode != default_settings.unfocused_mode
+    }) else {
+        return ours;
+    };
+
+    if existing.focused_mode == ours.focused_mode && existing.unfocused_mode == ours.unfocused_mode {
+        return ours;
+    }
+
+    match policy {
+        WinitSettingsPolicy::Overwrite => {
+            warn!("bevy_quick_response: overwriting existing WinitSettings ({existing:?}) with {ours:?}");
+            ours
+        }
+        WinitSettingsPolicy::KeepExisting => {
+            warn!("bevy_quick_response: keeping existing WinitSettings ({existing:?}) instead of {ours:?}");
+            existing.clone()
+        }
+        WinitSettingsPolicy::MergeWaitDurations => {
+            let merged = WinitSettings {
+                focused_mode: merge_wait(existing.focused_mode, ours.focused_mode),
+                unfocused_mode: merge_wait(existing.unfocused_mode, ours.unfocused_mode),
+            };
+            warn!("bevy_quick_response: merging wait durations of {ours:?} into existing WinitSettings ({existing:?})");
+            merged
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::QuickResponsePlugin;
+
+    use super::*;
+
+    #[test]
+    fn test_winit_settings_policy() {
+        let wait = Duration::from_secs_f64(1.0 / 30.0);
+
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .insert_resource(WinitSettings::desktop_app())
+            .add_plugins(QuickResponsePlugin::fast_vsync(30.0, 120.0)
+                .with_winit_settings_policy(WinitSettingsPolicy::KeepExisting)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .insert_resource(WinitSettings::desktop_app())
+            .add_plugins(QuickResponsePlugin::fast_vsync(30.0, 120.0)
+                .with_winit_settings_policy(WinitSettingsPolicy::MergeWaitDurations)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::reactive(wait));
+        assert_eq!(app.world().resource::<WinitSettings>().unfocused_mode, UpdateMode::reactive_low_power(wait));
+
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .insert_resource(WinitSettings::desktop_app())
+            .add_plugins(QuickResponsePlugin::fast_vsync(30.0, 120.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test());
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::reactive_low_power(wait));
+
+        // inserted by a plugin added later
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(QuickResponsePlugin::fast_vsync(30.0, 120.0)
+                .with_winit_settings_policy(WinitSettingsPolicy::KeepExisting)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test())
+            .insert_resource(WinitSettings::desktop_app());
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, WinitSettings::desktop_app().focused_mode);
+
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(QuickResponsePlugin::fast_vsync(30.0, 120.0)
+                .with_no_default_plugins()
+                .with_no_framepace_for_test())
+            .insert_resource(WinitSettings::desktop_app());
+        app.update();
+        assert_eq!(app.world().resource::<WinitSettings>().focused_mode, UpdateMode::reactive_low_power(wait));
+    }
+}